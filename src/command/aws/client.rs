@@ -0,0 +1,100 @@
+use std::error;
+use std::str::FromStr;
+
+use rusoto_codedeploy::CodeDeployClient;
+use rusoto_core::{Client, HttpClient, Region};
+use rusoto_credential::{AutoRefreshingProvider, ChainProvider, ProfileProvider};
+use rusoto_ecs::EcsClient;
+use rusoto_events::EventBridgeClient;
+use rusoto_logs::CloudWatchLogsClient;
+use rusoto_ssm::SsmClient;
+
+use super::json_client::JsonClient;
+use crate::config;
+
+pub struct ClientFactory {
+    client: Client,
+    region: Region,
+    dry_run: bool,
+}
+
+impl ClientFactory {
+    pub fn from_config(
+        config: &config::aws::AwsConfig,
+        dry_run: bool,
+    ) -> Result<Self, Box<dyn error::Error>> {
+        trace!("command::aws::ClientFactory::from_config");
+
+        let region = Self::region(config)?;
+        info!("aws region: {}", region.name());
+
+        let dispatcher = HttpClient::new()?;
+        let client = match config.profile.as_ref() {
+            Some(profile) => {
+                let mut provider = ProfileProvider::new()?;
+                provider.set_profile(profile.as_str());
+                Client::new_with(AutoRefreshingProvider::new(provider)?, dispatcher)
+            }
+            None => Client::new_with(
+                AutoRefreshingProvider::new(ChainProvider::new())?,
+                dispatcher,
+            ),
+        };
+
+        Ok(ClientFactory {
+            client,
+            region,
+            dry_run,
+        })
+    }
+
+    fn region(config: &config::aws::AwsConfig) -> Result<Region, Box<dyn error::Error>> {
+        let region = match config.region.as_ref() {
+            Some(name) => Region::from_str(name)?,
+            None => Region::ApNortheast1,
+        };
+
+        match config.endpoint_url.as_ref() {
+            Some(endpoint) => Ok(Region::Custom {
+                name: region.name().to_owned(),
+                endpoint: endpoint.to_owned(),
+            }),
+            None => Ok(region),
+        }
+    }
+
+    pub fn dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    pub fn codedeploy(&self) -> CodeDeployClient {
+        CodeDeployClient::new_with_client(self.client.clone(), self.region.clone())
+    }
+
+    pub fn ecs(&self) -> EcsClient {
+        EcsClient::new_with_client(self.client.clone(), self.region.clone())
+    }
+
+    /// ECS client of requests in JSON, for fields which `EcsClient` does not support yet.
+    pub fn ecs_json(&self) -> JsonClient {
+        JsonClient::new(
+            self.client.clone(),
+            self.region.clone(),
+            "ECS",
+            "ecs",
+            "AmazonEC2ContainerServiceV20141113",
+        )
+    }
+
+    pub fn events(&self) -> EventBridgeClient {
+        EventBridgeClient::new_with_client(self.client.clone(), self.region.clone())
+    }
+
+    pub fn logs(&self) -> CloudWatchLogsClient {
+        CloudWatchLogsClient::new_with_client(self.client.clone(), self.region.clone())
+    }
+
+    pub fn ssm(&self) -> SsmClient {
+        SsmClient::new_with_client(self.client.clone(), self.region.clone())
+    }
+}
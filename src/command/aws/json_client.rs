@@ -0,0 +1,119 @@
+use std::error;
+
+use rusoto_core::signature::SignedRequest;
+use rusoto_core::{Client, Region};
+use serde_json::Value;
+
+use crate::command::error::CommandError;
+
+/// Client sending requests of an API in the JSON protocol as they are,
+/// for fields which the SDK does not support yet.
+pub struct JsonClient {
+    client: Client,
+    region: Region,
+    service: &'static str,
+    signing_name: &'static str,
+    target_prefix: &'static str,
+}
+
+impl JsonClient {
+    pub fn new(
+        client: Client,
+        region: Region,
+        service: &'static str,
+        signing_name: &'static str,
+        target_prefix: &'static str,
+    ) -> Self {
+        JsonClient {
+            client,
+            region,
+            service,
+            signing_name,
+            target_prefix,
+        }
+    }
+
+    pub async fn request(
+        &self,
+        operation: &'static str,
+        body: &Value,
+    ) -> Result<Value, Box<dyn error::Error>> {
+        trace!("command::aws::JsonClient::request");
+
+        match self.send(operation, body).await? {
+            Ok(value) => Ok(value),
+            Err((type_, message)) => Err(Box::new(
+                self.error(operation, format!("{}: {}", type_, message)),
+            )),
+        }
+    }
+
+    /// Sends a request as `request`, but gives `None` when the service fails it
+    /// with the error type, such as `ClientException` for resources not found.
+    pub async fn request_or_none(
+        &self,
+        operation: &'static str,
+        body: &Value,
+        error_type: &str,
+    ) -> Result<Option<Value>, Box<dyn error::Error>> {
+        trace!("command::aws::JsonClient::request_or_none");
+
+        match self.send(operation, body).await? {
+            Ok(value) => Ok(Some(value)),
+            Err((type_, _)) if type_ == error_type => Ok(None),
+            Err((type_, message)) => Err(Box::new(
+                self.error(operation, format!("{}: {}", type_, message)),
+            )),
+        }
+    }
+
+    /// Sends a request, and returns the type and the message of the error
+    /// when the service fails it.
+    async fn send(
+        &self,
+        operation: &'static str,
+        body: &Value,
+    ) -> Result<Result<Value, (String, String)>, Box<dyn error::Error>> {
+        let mut request = SignedRequest::new("POST", self.signing_name, &self.region, "/");
+        request.set_content_type("application/x-amz-json-1.1".to_owned());
+        request.add_header(
+            "x-amz-target",
+            &format!("{}.{}", self.target_prefix, operation),
+        );
+        request.set_payload(Some(body.to_string()));
+
+        let mut response = self
+            .client
+            .sign_and_dispatch(request)
+            .await
+            .map_err(|e| self.error(operation, format!("{:?}", e)))?;
+        let response = response
+            .buffer()
+            .await
+            .map_err(|e| CommandError::aws_api(self.service, operation, e))?;
+
+        let value: Value = serde_json::from_slice(&response.body).unwrap_or(Value::Null);
+        if !response.status.is_success() {
+            // Errors are given as `__type` and `message`, whose case differs among services.
+            // The type may be prefixed with the namespace, as `namespace#ClientException`.
+            let type_ = value["__type"]
+                .as_str()
+                .and_then(|t| t.rsplit('#').next())
+                .unwrap_or("UnknownError");
+            let message = value["message"]
+                .as_str()
+                .or_else(|| value["Message"].as_str())
+                .unwrap_or_default();
+            return Ok(Err((type_.to_owned(), message.to_owned())));
+        }
+        Ok(Ok(value))
+    }
+
+    fn error(&self, operation: &'static str, detail: String) -> CommandError {
+        CommandError::AwsApi {
+            service: self.service,
+            operation,
+            source: detail.into(),
+        }
+    }
+}
@@ -0,0 +1,5 @@
+mod client;
+mod json_client;
+
+pub use self::client::ClientFactory;
+pub use self::json_client::JsonClient;
@@ -6,10 +6,12 @@ use rusoto_core::RusotoError;
 use rusoto_events;
 use rusoto_events::{EventBridge, EventBridgeClient};
 
+use crate::command::error::CommandError;
+use crate::command::mutation::Executer as MutationExecuter;
 use crate::config;
 
 #[async_trait]
-pub trait Executer {
+pub trait Executer: MutationExecuter {
     fn events_client(&self) -> &EventBridgeClient;
 
     async fn rule_exists(&self, rule_name: &str) -> Result<bool, Box<dyn error::Error>> {
@@ -25,7 +27,11 @@ pub trait Executer {
             Err(RusotoError::Service(rusoto_events::DescribeRuleError::ResourceNotFound(_))) => {
                 Ok(false)
             }
-            Err(e) => Err(Box::new(e)),
+            Err(e) => Err(Box::new(CommandError::aws_api(
+                "EventBridge",
+                "DescribeRule",
+                e,
+            ))),
         }
     }
 
@@ -37,7 +43,14 @@ pub trait Executer {
             ..Default::default()
         };
 
-        self.events_client().delete_rule(req).await?;
+        if !self.mutable("EventBridge DeleteRule", &req) {
+            return Ok(());
+        }
+
+        self.events_client()
+            .delete_rule(req)
+            .await
+            .map_err(|e| CommandError::aws_api("EventBridge", "DeleteRule", e))?;
         info!("Completed to delete-rule successfully");
 
         Ok(())
@@ -55,7 +68,14 @@ pub trait Executer {
             ..Default::default()
         };
 
-        self.events_client().put_rule(req).await?;
+        if !self.mutable("EventBridge PutRule", &req) {
+            return Ok(());
+        }
+
+        self.events_client()
+            .put_rule(req)
+            .await
+            .map_err(|e| CommandError::aws_api("EventBridge", "PutRule", e))?;
         info!("Completed to put-rule successfully");
 
         Ok(())
@@ -77,6 +97,18 @@ pub trait Executer {
                 task_count: Some(1),
                 task_definition_arn: task_definition_arn.to_owned(),
                 launch_type: config.launch_type.to_owned(),
+                capacity_provider_strategy: config
+                    .capacity_provider_strategy
+                    .as_ref()
+                    .map(|cps| cps.iter().map(|cp| cp.to_rusoto()).collect()),
+                placement_constraints: config
+                    .placement_constraints
+                    .as_ref()
+                    .map(|pcs| pcs.iter().map(|pc| pc.to_rusoto()).collect()),
+                placement_strategy: config
+                    .placement_strategy
+                    .as_ref()
+                    .map(|pss| pss.iter().map(|ps| ps.to_rusoto()).collect()),
                 platform_version: config.platform_version.to_owned(),
                 network_configuration: config.network_configuration.as_ref().map(|d| d.to_rusoto()),
                 ..Default::default()
@@ -91,7 +123,14 @@ pub trait Executer {
             ..Default::default()
         };
 
-        self.events_client().put_targets(req).await?;
+        if !self.mutable("EventBridge PutTargets", &req) {
+            return Ok(());
+        }
+
+        self.events_client()
+            .put_targets(req)
+            .await
+            .map_err(|e| CommandError::aws_api("EventBridge", "PutTargets", e))?;
         info!("Completed to put-targets successfully");
 
         Ok(())
@@ -105,14 +144,24 @@ pub trait Executer {
             ..Default::default()
         };
 
-        let res = self.events_client().list_targets_by_rule(req).await?;
+        let res = self
+            .events_client()
+            .list_targets_by_rule(req)
+            .await
+            .map_err(|e| CommandError::aws_api("EventBridge", "ListTargetsByRule", e))?;
         if let Some(targets) = res.targets {
             let req = rusoto_events::RemoveTargetsRequest {
                 rule: rule_name.to_owned(),
                 ids: targets.iter().map(|t| t.id.to_owned()).collect(),
                 ..Default::default()
             };
-            self.events_client().remove_targets(req).await?;
+            if !self.mutable("EventBridge RemoveTargets", &req) {
+                return Ok(());
+            }
+            self.events_client()
+                .remove_targets(req)
+                .await
+                .map_err(|e| CommandError::aws_api("EventBridge", "RemoveTargets", e))?;
         }
 
         Ok(())
@@ -0,0 +1,220 @@
+use async_trait::async_trait;
+use std::default::Default;
+use std::error;
+
+use rusoto_core::RusotoError;
+use rusoto_ecs;
+use rusoto_logs;
+use rusoto_logs::{CloudWatchLogs, CloudWatchLogsClient};
+
+use crate::command::error::CommandError;
+use crate::output;
+
+const AWSLOGS_DRIVER: &str = "awslogs";
+
+/// A log stream of a container using the awslogs driver.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogStream {
+    pub task_id: String,
+    pub container_name: String,
+    pub log_group: String,
+    pub log_stream: String,
+}
+
+impl LogStream {
+    /// Works out the log streams of the containers in a task.
+    /// Streams are named `prefix/container-name/task-id`, so containers without
+    /// `awslogs-stream-prefix` are skipped.
+    pub fn from_task(task_definition: &rusoto_ecs::TaskDefinition, task_arn: &str) -> Vec<Self> {
+        let task_id = task_arn.rsplit('/').next().unwrap_or(task_arn);
+
+        task_definition
+            .container_definitions
+            .as_ref()
+            .map(|cds| {
+                cds.iter()
+                    .filter_map(|cd| {
+                        let log_configuration = cd.log_configuration.as_ref()?;
+                        if log_configuration.log_driver != AWSLOGS_DRIVER {
+                            return None;
+                        }
+                        let options = log_configuration.options.as_ref()?;
+                        let container_name = cd.name.as_ref()?;
+
+                        Some(LogStream {
+                            task_id: task_id.to_owned(),
+                            container_name: container_name.to_owned(),
+                            log_group: options.get("awslogs-group")?.to_owned(),
+                            log_stream: format!(
+                                "{}/{}/{}",
+                                options.get("awslogs-stream-prefix")?,
+                                container_name,
+                                task_id
+                            ),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Position of reading a log stream.
+pub struct LogTail {
+    pub stream: LogStream,
+    prefix: String,
+    next_token: Option<String>,
+}
+
+impl LogTail {
+    /// Events are prefixed by the container name, and also by the task ID if `with_task_id`.
+    pub fn new(stream: LogStream, with_task_id: bool) -> Self {
+        let prefix = if with_task_id {
+            format!("{}/{}", stream.task_id, stream.container_name)
+        } else {
+            stream.container_name.to_owned()
+        };
+
+        LogTail {
+            stream,
+            prefix,
+            next_token: None,
+        }
+    }
+}
+
+#[async_trait]
+pub trait Executer {
+    fn logs_client(&self) -> &CloudWatchLogsClient;
+
+    /// Prints the events appended to the stream since the last call.
+    async fn tail_log_events(&self, tail: &mut LogTail) -> Result<(), Box<dyn error::Error>> {
+        trace!("command::cloudwatch_logs::Executer::tail_log_events");
+
+        loop {
+            let req = rusoto_logs::GetLogEventsRequest {
+                log_group_name: tail.stream.log_group.to_owned(),
+                log_stream_name: tail.stream.log_stream.to_owned(),
+                next_token: tail.next_token.to_owned(),
+                start_from_head: Some(true),
+                ..Default::default()
+            };
+
+            let res = match self.logs_client().get_log_events(req).await {
+                Ok(res) => res,
+                // The stream is created when the container starts.
+                Err(RusotoError::Service(rusoto_logs::GetLogEventsError::ResourceNotFound(_))) => {
+                    return Ok(());
+                }
+                Err(e) => {
+                    return Err(Box::new(CommandError::aws_api(
+                        "CloudWatchLogs",
+                        "GetLogEvents",
+                        e,
+                    )))
+                }
+            };
+
+            let events = res.events.unwrap_or_default();
+            for event in events.iter() {
+                if let Some(message) = event.message.as_ref() {
+                    output::PrintLine::print(&format!("[{}] {}", tail.prefix, message));
+                }
+            }
+
+            if res.next_forward_token.is_some() {
+                tail.next_token = res.next_forward_token;
+            }
+            if events.is_empty() {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Lists the events of the streams in a log group since the given time, in milliseconds.
+    async fn filter_log_events(
+        &self,
+        log_group: &str,
+        log_streams: &[String],
+        start_time: i64,
+        filter_pattern: Option<&str>,
+    ) -> Result<Vec<rusoto_logs::FilteredLogEvent>, Box<dyn error::Error>> {
+        trace!("command::cloudwatch_logs::Executer::filter_log_events");
+
+        let mut events = Vec::new();
+        // FilterLogEvents accepts up to 100 streams at once.
+        for chunk in log_streams.chunks(100) {
+            let mut next_token = None;
+            loop {
+                let req = rusoto_logs::FilterLogEventsRequest {
+                    log_group_name: log_group.to_owned(),
+                    log_stream_names: Some(chunk.to_vec()),
+                    start_time: Some(start_time),
+                    filter_pattern: filter_pattern.map(|p| p.to_owned()),
+                    next_token,
+                    ..Default::default()
+                };
+
+                let res = self
+                    .logs_client()
+                    .filter_log_events(req)
+                    .await
+                    .map_err(|e| CommandError::aws_api("CloudWatchLogs", "FilterLogEvents", e))?;
+                info!("Completed to filter log events successfully");
+
+                if let Some(page) = res.events {
+                    events.extend(page);
+                }
+
+                next_token = res.next_token;
+                if next_token.is_none() {
+                    break;
+                }
+            }
+        }
+
+        Ok(events)
+    }
+}
+
+#[test]
+fn test_log_streams_from_task() {
+    let container = |name: &str, driver: &str, prefix: Option<&str>| {
+        let mut options = ::std::collections::HashMap::new();
+        options.insert(String::from("awslogs-group"), String::from("racco"));
+        if let Some(prefix) = prefix {
+            options.insert(String::from("awslogs-stream-prefix"), prefix.to_owned());
+        }
+        rusoto_ecs::ContainerDefinition {
+            name: Some(name.to_owned()),
+            log_configuration: Some(rusoto_ecs::LogConfiguration {
+                log_driver: driver.to_owned(),
+                options: Some(options),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    };
+    let task_definition = rusoto_ecs::TaskDefinition {
+        container_definitions: Some(vec![
+            container("app", "awslogs", Some("racco-job")),
+            container("no-prefix", "awslogs", None),
+            container("fluent", "fluentd", Some("racco-job")),
+        ]),
+        ..Default::default()
+    };
+
+    let streams = LogStream::from_task(
+        &task_definition,
+        "arn:aws:ecs:ap-northeast-1:123456789012:task/racco-cluster/0123456789abcdef",
+    );
+    assert_eq!(
+        streams,
+        vec![LogStream {
+            task_id: String::from("0123456789abcdef"),
+            container_name: String::from("app"),
+            log_group: String::from("racco"),
+            log_stream: String::from("racco-job/app/0123456789abcdef"),
+        }]
+    );
+}
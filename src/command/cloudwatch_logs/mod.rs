@@ -0,0 +1,3 @@
+mod executer;
+
+pub use self::executer::{Executer, LogStream, LogTail};
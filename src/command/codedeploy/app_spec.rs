@@ -0,0 +1,128 @@
+use serde_json::{Map, Value};
+
+use crate::config;
+
+/// Builds the AppSpec of a deployment, which replaces the task set of the service with the
+/// task definition. The traffic is routed to the container of the first load balancer.
+pub fn app_spec(
+    service_conf: &config::ecs::Service,
+    deployment_conf: &config::codedeploy::Deployment,
+    task_definition_arn: &str,
+) -> Value {
+    let load_balancer = service_conf.load_balancers.iter().flatten().next();
+    let awsvpc_configuration = service_conf
+        .network_configuration
+        .as_ref()
+        .and_then(|nc| nc.awsvpc_configuration.as_ref());
+
+    let properties = json!({
+        "TaskDefinition": task_definition_arn,
+        "LoadBalancerInfo": {
+            "ContainerName": load_balancer.and_then(|lb| lb.container_name.to_owned()),
+            "ContainerPort": load_balancer.and_then(|lb| lb.container_port),
+        },
+        "PlatformVersion": service_conf.platform_version,
+        "NetworkConfiguration": awsvpc_configuration.map(|awsvpc| json!({
+            "AwsvpcConfiguration": {
+                "Subnets": awsvpc.subnets,
+                "SecurityGroups": awsvpc.security_groups,
+                "AssignPublicIp": awsvpc.assign_public_ip,
+            },
+        })),
+        "CapacityProviderStrategy": service_conf.capacity_provider_strategy.as_ref().map(|cps| {
+            cps.iter()
+                .map(|cp| json!({
+                    "CapacityProvider": cp.capacity_provider,
+                    "Weight": cp.weight,
+                    "Base": cp.base,
+                }))
+                .collect::<Vec<Value>>()
+        }),
+    });
+
+    without_nulls(json!({
+        "version": 0.0,
+        "Resources": [{
+            "TargetService": {
+                "Type": "AWS::ECS::Service",
+                "Properties": properties,
+            },
+        }],
+        "Hooks": deployment_conf.hooks.as_ref().map(|hooks| {
+            hooks
+                .iter()
+                .map(|hook| {
+                    let mut map = Map::new();
+                    map.insert(hook.event.to_owned(), json!(hook.function));
+                    Value::Object(map)
+                })
+                .collect::<Vec<Value>>()
+        }),
+    }))
+}
+
+// CodeDeploy rejects properties given as null, so unset ones are left out.
+fn without_nulls(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .filter(|(_, v)| !v.is_null())
+                .map(|(k, v)| (k, without_nulls(v)))
+                .collect(),
+        ),
+        Value::Array(values) => Value::Array(values.into_iter().map(without_nulls).collect()),
+        _ => value,
+    }
+}
+
+#[test]
+fn test_app_spec() {
+    let service_conf: config::ecs::Service = serde_yaml::from_str(
+        r"name: web
+deployment_controller:
+  type_: CODE_DEPLOY
+load_balancers:
+  - container_name: nginx
+    container_port: 80
+network_configuration:
+  awsvpc_configuration:
+    subnets: [subnet-0]
+task_definition:
+  family: web
+  container_definitions:
+    - name: nginx
+      image: nginx
+",
+    )
+    .unwrap();
+    let deployment_conf: config::codedeploy::Deployment = serde_yaml::from_str(
+        r"application_name: app
+deployment_group_name: group
+hooks:
+  - event: AfterAllowTestTraffic
+    function: check
+",
+    )
+    .unwrap();
+
+    let spec = app_spec(&service_conf, &deployment_conf, "arn:web:2");
+    assert_eq!(
+        spec,
+        json!({
+            "version": 0.0,
+            "Resources": [{
+                "TargetService": {
+                    "Type": "AWS::ECS::Service",
+                    "Properties": {
+                        "TaskDefinition": "arn:web:2",
+                        "LoadBalancerInfo": {"ContainerName": "nginx", "ContainerPort": 80},
+                        "NetworkConfiguration": {
+                            "AwsvpcConfiguration": {"Subnets": ["subnet-0"]},
+                        },
+                    },
+                },
+            }],
+            "Hooks": [{"AfterAllowTestTraffic": "check"}],
+        })
+    );
+}
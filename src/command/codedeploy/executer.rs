@@ -0,0 +1,246 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::default::Default;
+use std::error;
+use std::time::Duration;
+
+use rusoto_codedeploy;
+use rusoto_codedeploy::{CodeDeploy, CodeDeployClient};
+use rusoto_core::RusotoError;
+use serde_json::Value;
+use tokio::time::sleep;
+
+use crate::command::error::CommandError;
+use crate::command::mutation::Executer as MutationExecuter;
+use crate::config;
+use crate::output;
+
+/// Statuses of deployments which have not finished.
+const ACTIVE_STATUSES: [&str; 5] = ["Created", "Queued", "InProgress", "Baking", "Ready"];
+
+#[async_trait]
+pub trait Executer: MutationExecuter {
+    fn codedeploy_client(&self) -> &CodeDeployClient;
+
+    /// Creates a deployment with the AppSpec, returning its ID. It is `None` in dry-run mode.
+    async fn create_deployment(
+        &self,
+        deployment_conf: &config::codedeploy::Deployment,
+        app_spec: &Value,
+    ) -> Result<Option<String>, Box<dyn error::Error>> {
+        trace!("command::codedeploy::Executer::create_deployment");
+
+        let req = rusoto_codedeploy::CreateDeploymentInput {
+            application_name: deployment_conf.application_name.to_owned(),
+            deployment_group_name: Some(deployment_conf.deployment_group_name.to_owned()),
+            deployment_config_name: deployment_conf.deployment_config_name.to_owned(),
+            revision: Some(rusoto_codedeploy::RevisionLocation {
+                revision_type: Some(String::from("AppSpecContent")),
+                app_spec_content: Some(rusoto_codedeploy::AppSpecContent {
+                    content: Some(app_spec.to_string()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        if !self.mutable("CodeDeploy CreateDeployment", &req) {
+            return Ok(None);
+        }
+
+        let res = self
+            .codedeploy_client()
+            .create_deployment(req)
+            .await
+            .map_err(|e| CommandError::aws_api("CodeDeploy", "CreateDeployment", e))?;
+        info!("Completed to create deployment successfully");
+
+        res.deployment_id
+            .map(Some)
+            .ok_or(Box::new(CommandError::UnexpectedResponse {
+                operation: "CreateDeployment",
+                detail: "no deployment ID",
+            }))
+    }
+
+    async fn get_deployment(
+        &self,
+        deployment_id: &str,
+    ) -> Result<rusoto_codedeploy::DeploymentInfo, Box<dyn error::Error>> {
+        trace!("command::codedeploy::Executer::get_deployment");
+
+        let req = rusoto_codedeploy::GetDeploymentInput {
+            deployment_id: deployment_id.to_owned(),
+        };
+
+        let res = self
+            .codedeploy_client()
+            .get_deployment(req)
+            .await
+            .map_err(|e| CommandError::aws_api("CodeDeploy", "GetDeployment", e))?;
+        info!("Completed to get deployment successfully");
+
+        res.deployment_info
+            .ok_or(Box::new(CommandError::UnexpectedResponse {
+                operation: "GetDeployment",
+                detail: "no deployment info",
+            }))
+    }
+
+    /// Lists the lifecycle events of the ECS service in a deployment.
+    /// The target is `cluster:service`, which exists once the deployment starts.
+    async fn ecs_lifecycle_events(
+        &self,
+        deployment_id: &str,
+        target_id: &str,
+    ) -> Result<Vec<rusoto_codedeploy::LifecycleEvent>, Box<dyn error::Error>> {
+        trace!("command::codedeploy::Executer::ecs_lifecycle_events");
+
+        let req = rusoto_codedeploy::GetDeploymentTargetInput {
+            deployment_id: Some(deployment_id.to_owned()),
+            target_id: Some(target_id.to_owned()),
+        };
+
+        let res = match self.codedeploy_client().get_deployment_target(req).await {
+            Ok(res) => res,
+            Err(RusotoError::Service(
+                rusoto_codedeploy::GetDeploymentTargetError::DeploymentNotStarted(_),
+            ))
+            | Err(RusotoError::Service(
+                rusoto_codedeploy::GetDeploymentTargetError::DeploymentTargetDoesNotExist(_),
+            )) => return Ok(vec![]),
+            Err(e) => {
+                return Err(Box::new(CommandError::aws_api(
+                    "CodeDeploy",
+                    "GetDeploymentTarget",
+                    e,
+                )))
+            }
+        };
+        info!("Completed to get deployment target successfully");
+
+        Ok(res
+            .deployment_target
+            .and_then(|target| target.ecs_target)
+            .and_then(|ecs_target| ecs_target.lifecycle_events)
+            .unwrap_or_default())
+    }
+
+    /// Finds the deployment of the deployment group which has not finished.
+    async fn active_deployment(
+        &self,
+        deployment_conf: &config::codedeploy::Deployment,
+    ) -> Result<Option<String>, Box<dyn error::Error>> {
+        trace!("command::codedeploy::Executer::active_deployment");
+
+        let req = rusoto_codedeploy::ListDeploymentsInput {
+            application_name: Some(deployment_conf.application_name.to_owned()),
+            deployment_group_name: Some(deployment_conf.deployment_group_name.to_owned()),
+            include_only_statuses: Some(ACTIVE_STATUSES.iter().map(|s| (*s).to_owned()).collect()),
+            ..Default::default()
+        };
+
+        let res = self
+            .codedeploy_client()
+            .list_deployments(req)
+            .await
+            .map_err(|e| CommandError::aws_api("CodeDeploy", "ListDeployments", e))?;
+        info!("Completed to list deployments successfully");
+
+        // A deployment group runs one deployment at a time.
+        Ok(res.deployments.and_then(|ids| ids.into_iter().next()))
+    }
+
+    /// Continues a deployment waiting for the traffic to be rerouted (`READY_WAIT`),
+    /// or one keeping the original tasks (`TERMINATION_WAIT`).
+    async fn continue_deployment(
+        &self,
+        deployment_id: &str,
+        wait_type: &str,
+    ) -> Result<(), Box<dyn error::Error>> {
+        trace!("command::codedeploy::Executer::continue_deployment");
+
+        let req = rusoto_codedeploy::ContinueDeploymentInput {
+            deployment_id: Some(deployment_id.to_owned()),
+            deployment_wait_type: Some(wait_type.to_owned()),
+        };
+
+        if !self.mutable("CodeDeploy ContinueDeployment", &req) {
+            return Ok(());
+        }
+
+        self.codedeploy_client()
+            .continue_deployment(req)
+            .await
+            .map_err(|e| CommandError::aws_api("CodeDeploy", "ContinueDeployment", e))?;
+        info!("Completed to continue deployment successfully");
+        Ok(())
+    }
+
+    async fn stop_deployment(
+        &self,
+        deployment_id: &str,
+        auto_rollback: bool,
+    ) -> Result<(), Box<dyn error::Error>> {
+        trace!("command::codedeploy::Executer::stop_deployment");
+
+        let req = rusoto_codedeploy::StopDeploymentInput {
+            deployment_id: deployment_id.to_owned(),
+            auto_rollback_enabled: Some(auto_rollback),
+        };
+
+        if !self.mutable("CodeDeploy StopDeployment", &req) {
+            return Ok(());
+        }
+
+        self.codedeploy_client()
+            .stop_deployment(req)
+            .await
+            .map_err(|e| CommandError::aws_api("CodeDeploy", "StopDeployment", e))?;
+        info!("Completed to stop deployment successfully");
+        Ok(())
+    }
+
+    /// Polls the deployment, printing its lifecycle events, until it succeeds or gets ready
+    /// for the traffic to be rerouted. Returns the status.
+    async fn poll_deployment(
+        &self,
+        deployment_id: &str,
+        target_id: &str,
+        poll_interval: Duration,
+    ) -> Result<String, Box<dyn error::Error>> {
+        trace!("command::codedeploy::Executer::poll_deployment");
+
+        let mut reported: HashMap<String, String> = HashMap::new();
+        loop {
+            let events = self.ecs_lifecycle_events(deployment_id, target_id).await?;
+            for event in events.iter() {
+                let (name, status) =
+                    match (event.lifecycle_event_name.as_ref(), event.status.as_ref()) {
+                        (Some(name), Some(status)) => (name, status),
+                        _ => continue,
+                    };
+                if reported.get(name) != Some(status) {
+                    output::PrintLine::info(&format!("{}: {}", name, status));
+                    reported.insert(name.to_owned(), status.to_owned());
+                }
+            }
+
+            let deployment = self.get_deployment(deployment_id).await?;
+            let status = deployment.status.unwrap_or_default();
+            match status.as_str() {
+                "Succeeded" | "Ready" => return Ok(status),
+                "Failed" | "Stopped" => {
+                    let reason = deployment
+                        .error_information
+                        .and_then(|e| e.message)
+                        .unwrap_or_else(|| format!("The deployment is {}", status));
+                    return Err(Box::new(CommandError::DeploymentFailed(reason)));
+                }
+                _ => {}
+            }
+            sleep(poll_interval).await;
+        }
+    }
+}
@@ -0,0 +1,5 @@
+mod app_spec;
+mod executer;
+
+pub use self::app_spec::app_spec;
+pub use self::executer::Executer;
@@ -4,6 +4,7 @@ use clap;
 use serde_yaml;
 
 use crate::config;
+use crate::output;
 
 pub struct Command<'c> {
     config: &'c config::command::Config,
@@ -25,7 +26,12 @@ impl<'c> Command<'c> {
     pub fn run(&self) -> Result<(), Box<dyn error::Error>> {
         trace!("command::config::Command::run");
 
-        println!("{}", serde_yaml::to_string(&self.config).unwrap());
+        match output::format() {
+            output::Format::Text => {
+                println!("{}", serde_yaml::to_string(&self.config).unwrap())
+            }
+            output::Format::Json => output::PrintResult::emit(&self.config),
+        }
 
         Ok(())
     }
@@ -0,0 +1,459 @@
+use std::collections::BTreeMap;
+
+use rusoto_ecs;
+use serde_json;
+use serde_json::Value;
+
+use super::task_definition;
+use crate::config;
+
+#[derive(Debug, Serialize)]
+pub struct Change {
+    pub path: String,
+    pub current: Option<Value>,
+    pub desired: Option<Value>,
+}
+
+/// Lists changes between the registered task definition and the one in config.
+pub fn task_definition_changes(
+    task_definition_conf: &config::ecs::TaskDefinition,
+    current_task_definition: Option<&rusoto_ecs::TaskDefinition>,
+) -> Vec<Change> {
+    let desired = task_definition::normalize(&task_definition_conf.to_rusoto());
+    let current = current_task_definition
+        .map(|td| task_definition::normalize(&task_definition::to_request(td)));
+
+    let desired_value = serde_json::to_value(&desired).ok();
+    let current_value = current.and_then(|c| serde_json::to_value(&c).ok());
+
+    let mut changes = Vec::new();
+    diff_values(
+        "",
+        current_value.as_ref(),
+        desired_value.as_ref(),
+        false,
+        &mut changes,
+    );
+    changes
+}
+
+/// Compares `runtimePlatform` of the registered task definition, which is described in JSON
+/// since the SDK drops it from the response, with the one in config.
+pub fn runtime_platform_change(
+    task_definition_conf: &config::ecs::TaskDefinition,
+    current_runtime_platform: Option<&Value>,
+) -> Option<Change> {
+    let current = task_definition::normalize_runtime_platform(current_runtime_platform.cloned());
+    let desired = task_definition::normalize_runtime_platform(
+        task_definition_conf
+            .runtime_platform
+            .as_ref()
+            .map(|rp| rp.to_json()),
+    );
+    if current == desired {
+        return None;
+    }
+    Some(Change {
+        path: String::from("runtimePlatform"),
+        current: Some(current),
+        desired: Some(desired),
+    })
+}
+
+/// Lists changes between the running service and the one in config.
+/// Fields which are not set in config are left unchanged by a deployment, so they are ignored.
+pub fn service_changes(
+    service_conf: &config::ecs::Service,
+    current_service: Option<&rusoto_ecs::Service>,
+) -> Vec<Change> {
+    let desired = rusoto_ecs::UpdateServiceRequest {
+        service: service_conf.name.to_owned(),
+        desired_count: service_conf.desired_count,
+        deployment_configuration: service_conf
+            .deployment_configuration
+            .as_ref()
+            .map(|d| d.to_rusoto()),
+        capacity_provider_strategy: service_conf
+            .capacity_provider_strategy
+            .as_ref()
+            .map(|cps| cps.iter().map(|cp| cp.to_rusoto()).collect()),
+        placement_constraints: service_conf
+            .placement_constraints
+            .as_ref()
+            .map(|pcs| pcs.iter().map(|pc| pc.to_rusoto()).collect()),
+        placement_strategy: service_conf
+            .placement_strategy
+            .as_ref()
+            .map(|pss| pss.iter().map(|ps| ps.to_rusoto()).collect()),
+        network_configuration: service_conf
+            .network_configuration
+            .as_ref()
+            .map(|e| e.to_rusoto()),
+        platform_version: service_conf.platform_version.to_owned(),
+        enable_execute_command: service_conf.enable_execute_command,
+        health_check_grace_period_seconds: service_conf.health_check_grace_period_seconds,
+        ..Default::default()
+    };
+    let current = current_service.map(|s| rusoto_ecs::UpdateServiceRequest {
+        service: s.service_name.to_owned().unwrap_or_default(),
+        desired_count: s.desired_count,
+        deployment_configuration: s.deployment_configuration.to_owned(),
+        capacity_provider_strategy: s.capacity_provider_strategy.to_owned(),
+        placement_constraints: s.placement_constraints.to_owned(),
+        placement_strategy: s.placement_strategy.to_owned(),
+        network_configuration: s.network_configuration.to_owned(),
+        platform_version: s.platform_version.to_owned(),
+        enable_execute_command: s.enable_execute_command,
+        health_check_grace_period_seconds: s.health_check_grace_period_seconds,
+        ..Default::default()
+    });
+
+    let desired_value = serde_json::to_value(&desired).ok();
+    let current_value = current.and_then(|c| serde_json::to_value(&c).ok());
+
+    let mut changes = Vec::new();
+    diff_values(
+        "",
+        current_value.as_ref(),
+        desired_value.as_ref(),
+        true,
+        &mut changes,
+    );
+
+    // The SDK lacks these fields of UpdateService, so they are compared in CreateService.
+    // Load balancers and service registries of the services deployed by CodeDeploy
+    // or the EXTERNAL controller are given to their task sets, not updated.
+    let updates_task_sets =
+        service_conf.uses_code_deploy() || service_conf.uses_external_controller();
+    let desired = rusoto_ecs::CreateServiceRequest {
+        service_name: service_conf.name.to_owned(),
+        load_balancers: service_conf
+            .load_balancers
+            .as_ref()
+            .filter(|_| !updates_task_sets)
+            .map(|lbs| lbs.iter().map(|lb| lb.to_rusoto()).collect()),
+        service_registries: service_conf
+            .service_registries
+            .as_ref()
+            .filter(|_| !updates_task_sets)
+            .map(|srs| srs.iter().map(|sr| sr.to_rusoto()).collect()),
+        propagate_tags: service_conf.propagate_tags.to_owned(),
+        enable_ecs_managed_tags: service_conf.enable_ecs_managed_tags,
+        ..Default::default()
+    };
+    let current = current_service.map(|s| rusoto_ecs::CreateServiceRequest {
+        service_name: service_conf.name.to_owned(),
+        load_balancers: s.load_balancers.to_owned(),
+        service_registries: s.service_registries.to_owned(),
+        propagate_tags: s.propagate_tags.to_owned(),
+        enable_ecs_managed_tags: s.enable_ecs_managed_tags,
+        ..Default::default()
+    });
+
+    let desired_value = serde_json::to_value(&desired).ok();
+    let current_value = current.and_then(|c| serde_json::to_value(&c).ok());
+    diff_values(
+        "",
+        current_value.as_ref(),
+        desired_value.as_ref(),
+        true,
+        &mut changes,
+    );
+    changes
+}
+
+/// Lists changes of the fields which UpdateService cannot change,
+/// so that the service has to be recreated to apply them.
+pub fn recreation_changes(
+    service_conf: &config::ecs::Service,
+    current_service: Option<&rusoto_ecs::Service>,
+) -> Vec<Change> {
+    let current_service = match current_service {
+        Some(s) => s,
+        None => return vec![],
+    };
+
+    let desired = rusoto_ecs::CreateServiceRequest {
+        service_name: service_conf.name.to_owned(),
+        launch_type: service_conf.launch_type.to_owned(),
+        scheduling_strategy: service_conf.scheduling_strategy.to_owned(),
+        deployment_controller: service_conf
+            .deployment_controller
+            .as_ref()
+            .map(|d| d.to_rusoto()),
+        ..Default::default()
+    };
+    let current = rusoto_ecs::CreateServiceRequest {
+        service_name: service_conf.name.to_owned(),
+        launch_type: current_service.launch_type.to_owned(),
+        scheduling_strategy: current_service.scheduling_strategy.to_owned(),
+        deployment_controller: current_service.deployment_controller.to_owned(),
+        ..Default::default()
+    };
+
+    let desired_value = serde_json::to_value(&desired).ok();
+    let current_value = serde_json::to_value(&current).ok();
+
+    let mut changes = Vec::new();
+    diff_values(
+        "",
+        current_value.as_ref(),
+        desired_value.as_ref(),
+        true,
+        &mut changes,
+    );
+    changes
+}
+
+// When `partial` is set, values missing in `desired` are not treated as removals.
+// An item of a named list which is added or removed is reported as a whole,
+// while an object missing on one side is traversed for each field.
+fn diff_values(
+    path: &str,
+    current: Option<&Value>,
+    desired: Option<&Value>,
+    partial: bool,
+    changes: &mut Vec<Change>,
+) {
+    diff_values_inner(path, current, desired, partial, false, changes)
+}
+
+fn diff_values_inner(
+    path: &str,
+    current: Option<&Value>,
+    desired: Option<&Value>,
+    partial: bool,
+    item: bool,
+    changes: &mut Vec<Change>,
+) {
+    if partial && desired.is_none() {
+        return;
+    }
+    if current == desired {
+        return;
+    }
+
+    let current_children = current.and_then(children);
+    let desired_children = desired.and_then(children);
+
+    let (current_children, desired_children) = match (current_children, desired_children) {
+        (Some(c), Some(d)) => (c, d),
+        (Some(c), None) if desired.is_none() && !item => (c, BTreeMap::new()),
+        (None, Some(d)) if current.is_none() && !item => (BTreeMap::new(), d),
+        _ => {
+            changes.push(Change {
+                path: path.to_owned(),
+                current: current.cloned(),
+                desired: desired.cloned(),
+            });
+            return;
+        }
+    };
+
+    let mut segments: Vec<&String> = current_children
+        .keys()
+        .chain(desired_children.keys())
+        .collect();
+    segments.sort();
+    segments.dedup();
+
+    for segment in segments {
+        let child_path = if path.is_empty() {
+            segment.trim_start_matches('.').to_owned()
+        } else {
+            format!("{}{}", path, segment)
+        };
+        diff_values_inner(
+            &child_path,
+            current_children.get(segment).cloned(),
+            desired_children.get(segment).cloned(),
+            partial,
+            segment.starts_with('['),
+            changes,
+        );
+    }
+}
+
+// Objects are traversed by their keys, and lists of named items (containers,
+// environment variables, secrets, ...) by their names.
+fn children(value: &Value) -> Option<BTreeMap<String, &Value>> {
+    match value {
+        Value::Object(map) => Some(map.iter().map(|(k, v)| (format!(".{}", k), v)).collect()),
+        Value::Array(list) => {
+            let names: Option<Vec<&str>> = list
+                .iter()
+                .map(|item| item.get("Name").and_then(Value::as_str))
+                .collect();
+            match names {
+                Some(names) if !names.is_empty() => Some(
+                    names
+                        .into_iter()
+                        .zip(list.iter())
+                        .map(|(name, item)| (format!("[{}]", name), item))
+                        .collect(),
+                ),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+#[test]
+fn test_diff_values_named_list() {
+    let current = json!({
+        "ContainerDefinitions": [
+            {"Name": "web", "Image": "web:v1", "Environment": [{"Name": "FOO", "Value": "1"}]},
+            {"Name": "sidecar", "Image": "sidecar:v1"}
+        ]
+    });
+    let desired = json!({
+        "ContainerDefinitions": [
+            {"Name": "web", "Image": "web:v2", "Environment": [{"Name": "BAR", "Value": "2"}]},
+            {"Name": "sidecar", "Image": "sidecar:v1"}
+        ]
+    });
+
+    let mut changes = Vec::new();
+    diff_values("", Some(&current), Some(&desired), false, &mut changes);
+
+    let paths: Vec<&str> = changes.iter().map(|c| c.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        vec![
+            "ContainerDefinitions[web].Environment[BAR]",
+            "ContainerDefinitions[web].Environment[FOO]",
+            "ContainerDefinitions[web].Image",
+        ]
+    );
+    assert_eq!(changes[2].current, Some(json!("web:v1")));
+    assert_eq!(changes[2].desired, Some(json!("web:v2")));
+}
+
+#[test]
+fn test_diff_values_partial() {
+    let current = json!({"DesiredCount": 2, "PlatformVersion": "1.4.0"});
+    let desired = json!({"DesiredCount": 4});
+
+    let mut changes = Vec::new();
+    diff_values("", Some(&current), Some(&desired), true, &mut changes);
+
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].path, "DesiredCount");
+}
+
+#[test]
+fn test_recreation_changes() {
+    let service_conf: config::ecs::Service = serde_yaml::from_str(
+        r"name: test
+launch_type: FARGATE
+task_definition:
+  family: test
+  container_definitions:
+    - name: test
+      image: 'test.dkr.com/racco/test:latest'
+",
+    )
+    .unwrap();
+    // ECS fills in the defaults of the fields which are not set in config.
+    let current_service = rusoto_ecs::Service {
+        service_name: Some(String::from("test")),
+        launch_type: Some(String::from("FARGATE")),
+        scheduling_strategy: Some(String::from("REPLICA")),
+        deployment_controller: Some(rusoto_ecs::DeploymentController {
+            type_: String::from("ECS"),
+        }),
+        propagate_tags: Some(String::from("NONE")),
+        enable_ecs_managed_tags: Some(false),
+        ..Default::default()
+    };
+
+    assert!(recreation_changes(&service_conf, Some(&current_service)).is_empty());
+    assert!(recreation_changes(&service_conf, None).is_empty());
+
+    let service_conf = config::ecs::Service {
+        launch_type: Some(String::from("EC2")),
+        ..service_conf
+    };
+    let changes = recreation_changes(&service_conf, Some(&current_service));
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].path, "launchType");
+    assert_eq!(changes[0].current, Some(json!("FARGATE")));
+    assert_eq!(changes[0].desired, Some(json!("EC2")));
+}
+
+#[test]
+fn test_runtime_platform_change() {
+    let task_definition_conf: config::ecs::TaskDefinition = serde_yaml::from_str(
+        r"family: test
+container_definitions:
+  - name: test
+    image: 'test.dkr.com/racco/test:latest'
+",
+    )
+    .unwrap();
+
+    assert!(runtime_platform_change(&task_definition_conf, None).is_none());
+    assert!(runtime_platform_change(
+        &task_definition_conf,
+        Some(&json!({"cpuArchitecture": "X86_64", "operatingSystemFamily": "LINUX"}))
+    )
+    .is_none());
+
+    // Removing `runtime_platform` from config returns the tasks to X86_64.
+    let change = runtime_platform_change(
+        &task_definition_conf,
+        Some(&json!({"cpuArchitecture": "ARM64"})),
+    )
+    .unwrap();
+    assert_eq!(change.path, "runtimePlatform");
+    assert_eq!(
+        change.desired,
+        Some(json!({"cpuArchitecture": "X86_64", "operatingSystemFamily": "LINUX"}))
+    );
+}
+
+#[test]
+fn test_service_changes_of_load_balancers() {
+    let service_conf: config::ecs::Service = serde_yaml::from_str(
+        r"name: test
+load_balancers:
+  - container_name: test
+    container_port: 80
+    target_group_arn: 'arn:aws:elasticloadbalancing:ap-northeast-1:000000000000:targetgroup/test/0'
+task_definition:
+  family: test
+  container_definitions:
+    - name: test
+      image: 'test.dkr.com/racco/test:latest'
+",
+    )
+    .unwrap();
+    let current_service = rusoto_ecs::Service {
+        service_name: Some(String::from("test")),
+        ..Default::default()
+    };
+
+    // UpdateService changes load balancers in place, without recreating the service.
+    assert!(recreation_changes(&service_conf, Some(&current_service)).is_empty());
+    let changes = service_changes(&service_conf, Some(&current_service));
+    assert_eq!(changes.len(), 1);
+    assert!(changes[0].path.starts_with("loadBalancers"));
+
+    // Those of the EXTERNAL controller are given to task sets.
+    let service_conf: config::ecs::Service = serde_yaml::from_str(
+        r"name: test
+deployment_controller:
+  type_: EXTERNAL
+load_balancers:
+  - container_name: test
+    container_port: 80
+task_definition:
+  family: test
+  container_definitions:
+    - name: test
+",
+    )
+    .unwrap();
+    assert!(service_changes(&service_conf, Some(&current_service)).is_empty());
+}
@@ -6,7 +6,9 @@ use rusoto_core::RusotoError;
 use rusoto_ecs;
 use rusoto_ecs::{Ecs, EcsClient};
 
+use super::task_definition;
 use crate::command::error::CommandError;
+use crate::command::mutation::Executer as MutationExecuter;
 use crate::config;
 
 pub struct TaskDescription {
@@ -15,7 +17,7 @@ pub struct TaskDescription {
 }
 
 #[async_trait]
-pub trait Executer {
+pub trait Executer: MutationExecuter {
     fn ecs_client(&self) -> &EcsClient;
 
     async fn describe_cluster(
@@ -29,7 +31,11 @@ pub trait Executer {
             ..Default::default()
         };
 
-        let res = self.ecs_client().describe_clusters(req).await?;
+        let res = self
+            .ecs_client()
+            .describe_clusters(req)
+            .await
+            .map_err(|e| CommandError::aws_api("ECS", "DescribeClusters", e))?;
         info!("Completed to describe clusters successfully");
 
         match res.clusters {
@@ -42,7 +48,10 @@ pub trait Executer {
                     .collect::<Vec<&rusoto_ecs::Cluster>>();
                 Ok(actives.first().cloned().cloned())
             }
-            _ => Err(Box::new(CommandError::Unknown)),
+            _ => Err(Box::new(CommandError::UnexpectedResponse {
+                operation: "DescribeClusters",
+                detail: "no clusters",
+            })),
         }
     }
 
@@ -52,8 +61,18 @@ pub trait Executer {
     ) -> Result<Option<rusoto_ecs::TaskDefinition>, Box<dyn error::Error>> {
         trace!("command::ecs::Executer::describe_latest_task_definition");
 
+        self.describe_task_definition(family).await
+    }
+
+    /// Describes a task definition given by its family, `family:revision` or ARN.
+    async fn describe_task_definition(
+        &self,
+        task_definition: &str,
+    ) -> Result<Option<rusoto_ecs::TaskDefinition>, Box<dyn error::Error>> {
+        trace!("command::ecs::Executer::describe_task_definition");
+
         let req = rusoto_ecs::DescribeTaskDefinitionRequest {
-            task_definition: family.to_owned(),
+            task_definition: task_definition.to_owned(),
             ..Default::default()
         };
 
@@ -63,46 +82,58 @@ pub trait Executer {
                 Ok(res.task_definition)
             }
             Err(RusotoError::Service(rusoto_ecs::DescribeTaskDefinitionError::Client(_))) => {
-                info!("Not found the task-definition: {}", family);
+                info!("Not found the task-definition: {}", task_definition);
                 Ok(None)
             }
-            Err(e) => Err(Box::new(e)),
+            Err(e) => Err(Box::new(CommandError::aws_api(
+                "ECS",
+                "DescribeTaskDefinition",
+                e,
+            ))),
         }
     }
 
-    async fn register_task_definition(
+    /// Lists ARNs of active revisions in the family, from the latest one.
+    async fn list_task_definition_arns(
         &self,
-        task_definition_conf: &config::ecs::TaskDefinition,
-    ) -> Result<rusoto_ecs::TaskDefinition, Box<dyn error::Error>> {
-        trace!("command::ecs::Executer::register_task_definition");
-        let req = rusoto_ecs::RegisterTaskDefinitionRequest {
-            family: task_definition_conf.family.to_owned(),
-            task_role_arn: task_definition_conf.task_role_arn.to_owned(),
-            network_mode: task_definition_conf.network_mode.to_owned(),
-            volumes: task_definition_conf
-                .volumes
-                .as_ref()
-                .map(|volumes| volumes.iter().map(|v| v.to_rusoto()).collect()),
-            container_definitions: task_definition_conf
-                .container_definitions
-                .iter()
-                .map(|cd| cd.to_rusoto())
-                .collect(),
-            execution_role_arn: task_definition_conf.execution_role_arn.to_owned(),
-            requires_compatibilities: task_definition_conf.requires_compatibilities.to_owned(),
-            cpu: task_definition_conf.cpu.to_owned(),
-            memory: task_definition_conf.memory.to_owned(),
-            proxy_configuration: task_definition_conf
-                .proxy_configuration
-                .as_ref()
-                .map(|pc| pc.to_rusoto()),
-            ..Default::default()
-        };
+        family: &str,
+        max_count: usize,
+    ) -> Result<Vec<String>, Box<dyn error::Error>> {
+        trace!("command::ecs::Executer::list_task_definition_arns");
+
+        // `family_prefix` also matches other families which start with the name.
+        let family_part = format!("task-definition/{}:", family);
+
+        let mut arns = Vec::new();
+        let mut next_token = None;
+        loop {
+            let req = rusoto_ecs::ListTaskDefinitionsRequest {
+                family_prefix: Some(family.to_owned()),
+                status: Some(String::from("ACTIVE")),
+                sort: Some(String::from("DESC")),
+                next_token,
+                ..Default::default()
+            };
+
+            let res = self
+                .ecs_client()
+                .list_task_definitions(req)
+                .await
+                .map_err(|e| CommandError::aws_api("ECS", "ListTaskDefinitions", e))?;
+            info!("Completed to list task_definitions successfully");
+
+            if let Some(page) = res.task_definition_arns {
+                arns.extend(page.into_iter().filter(|arn| arn.contains(&family_part)));
+            }
 
-        let res = self.ecs_client().register_task_definition(req).await?;
-        info!("Completed to register task_definition successfully");
+            next_token = res.next_token;
+            if arns.len() >= max_count || next_token.is_none() {
+                break;
+            }
+        }
 
-        res.task_definition.ok_or(Box::new(CommandError::Unknown))
+        arns.truncate(max_count);
+        Ok(arns)
     }
 
     async fn create_service(
@@ -113,17 +144,36 @@ pub trait Executer {
     ) -> Result<rusoto_ecs::Service, Box<dyn error::Error>> {
         trace!("command::ecs::Executer::create_service");
 
-        let req = rusoto_ecs::CreateServiceRequest {
+        let mut req = rusoto_ecs::CreateServiceRequest {
             cluster: Some(cluster.to_owned()),
             service_name: service_conf.name.to_owned(),
             desired_count: service_conf.desired_count,
-            //deployment_configuration: service_conf.deployment_configuration.as_ref().map(|d| d.to_rusoto()),
+            deployment_configuration: service_conf
+                .deployment_configuration
+                .as_ref()
+                .map(|d| d.to_rusoto()),
+            deployment_controller: service_conf
+                .deployment_controller
+                .as_ref()
+                .map(|d| d.to_rusoto()),
             load_balancers: service_conf
                 .load_balancers
                 .as_ref()
                 .map(|lbs| lbs.iter().map(|lb| lb.to_rusoto()).collect()),
             role: service_conf.role.to_owned(),
             launch_type: service_conf.launch_type.to_owned(),
+            capacity_provider_strategy: service_conf
+                .capacity_provider_strategy
+                .as_ref()
+                .map(|cps| cps.iter().map(|cp| cp.to_rusoto()).collect()),
+            placement_constraints: service_conf
+                .placement_constraints
+                .as_ref()
+                .map(|pcs| pcs.iter().map(|pc| pc.to_rusoto()).collect()),
+            placement_strategy: service_conf
+                .placement_strategy
+                .as_ref()
+                .map(|pss| pss.iter().map(|ps| ps.to_rusoto()).collect()),
             network_configuration: service_conf
                 .network_configuration
                 .as_ref()
@@ -135,6 +185,10 @@ pub trait Executer {
             task_definition: Some(task_definition.to_owned()),
             platform_version: service_conf.platform_version.to_owned(),
             enable_execute_command: service_conf.enable_execute_command,
+            health_check_grace_period_seconds: service_conf.health_check_grace_period_seconds,
+            scheduling_strategy: service_conf.scheduling_strategy.to_owned(),
+            propagate_tags: service_conf.propagate_tags.to_owned(),
+            enable_ecs_managed_tags: service_conf.enable_ecs_managed_tags,
             tags: service_conf
                 .tags
                 .as_ref()
@@ -142,10 +196,42 @@ pub trait Executer {
             ..Default::default()
         };
 
-        let res = self.ecs_client().create_service(req).await?;
+        // The task definition and the settings of the tasks belong to task sets of the service.
+        if service_conf.uses_external_controller() {
+            req = rusoto_ecs::CreateServiceRequest {
+                load_balancers: None,
+                role: None,
+                launch_type: None,
+                capacity_provider_strategy: None,
+                network_configuration: None,
+                service_registries: None,
+                task_definition: None,
+                platform_version: None,
+                ..req
+            };
+        }
+
+        if !self.mutable("ECS CreateService", &req) {
+            return Ok(rusoto_ecs::Service {
+                service_name: Some(req.service_name),
+                desired_count: req.desired_count,
+                task_definition: req.task_definition,
+                ..Default::default()
+            });
+        }
+
+        let res = self
+            .ecs_client()
+            .create_service(req)
+            .await
+            .map_err(|e| CommandError::aws_api("ECS", "CreateService", e))?;
         info!("Completed to create service successfully");
 
-        res.service.ok_or(Box::new(CommandError::Unknown))
+        res.service
+            .ok_or(Box::new(CommandError::UnexpectedResponse {
+                operation: "CreateService",
+                detail: "no service",
+            }))
     }
 
     async fn describe_service(
@@ -161,7 +247,11 @@ pub trait Executer {
             ..Default::default()
         };
 
-        let res = self.ecs_client().describe_services(req).await?;
+        let res = self
+            .ecs_client()
+            .describe_services(req)
+            .await
+            .map_err(|e| CommandError::aws_api("ECS", "DescribeServices", e))?;
         info!("Completed to describe services successfully");
 
         match res.services {
@@ -174,7 +264,10 @@ pub trait Executer {
                     .collect::<Vec<&rusoto_ecs::Service>>();
                 Ok(actives.first().cloned().cloned())
             }
-            _ => Err(Box::new(CommandError::Unknown)),
+            _ => Err(Box::new(CommandError::UnexpectedResponse {
+                operation: "DescribeServices",
+                detail: "no services",
+            })),
         }
     }
 
@@ -183,125 +276,461 @@ pub trait Executer {
         cluster: &str,
         service_conf: &config::ecs::Service,
         task_definition: &rusoto_ecs::TaskDefinition,
+        force_new_deployment: bool,
     ) -> Result<rusoto_ecs::Service, Box<dyn error::Error>> {
         trace!("command::ecs::Executer::update_service");
 
-        if task_definition.task_definition_arn.is_none() {
-            return Err(Box::new(CommandError::Unknown));
+        let req =
+            update_service_request(cluster, service_conf, task_definition, force_new_deployment)?;
+
+        if !self.mutable("ECS UpdateService", &req) {
+            return Ok(rusoto_ecs::Service {
+                service_name: Some(req.service),
+                desired_count: req.desired_count,
+                task_definition: req.task_definition,
+                ..Default::default()
+            });
         }
 
+        let res = self
+            .ecs_client()
+            .update_service(req)
+            .await
+            .map_err(|e| CommandError::aws_api("ECS", "UpdateService", e))?;
+        info!("Completed to update service successfully");
+
+        res.service
+            .ok_or(Box::new(CommandError::UnexpectedResponse {
+                operation: "UpdateService",
+                detail: "no service",
+            }))
+    }
+
+    async fn update_service_task_definition(
+        &self,
+        cluster: &str,
+        service_name: &str,
+        task_definition_arn: &str,
+    ) -> Result<rusoto_ecs::Service, Box<dyn error::Error>> {
+        trace!("command::ecs::Executer::update_service_task_definition");
+
         let req = rusoto_ecs::UpdateServiceRequest {
-            service: service_conf.name.to_owned(),
+            service: service_name.to_owned(),
             cluster: Some(cluster.to_owned()),
-            desired_count: service_conf.desired_count,
-            deployment_configuration: service_conf
-                .deployment_configuration
+            task_definition: Some(task_definition_arn.to_owned()),
+            ..Default::default()
+        };
+
+        if !self.mutable("ECS UpdateService", &req) {
+            return Ok(rusoto_ecs::Service {
+                service_name: Some(req.service),
+                task_definition: req.task_definition,
+                ..Default::default()
+            });
+        }
+
+        let res = self
+            .ecs_client()
+            .update_service(req)
+            .await
+            .map_err(|e| CommandError::aws_api("ECS", "UpdateService", e))?;
+        info!("Completed to update service successfully");
+
+        res.service
+            .ok_or(Box::new(CommandError::UnexpectedResponse {
+                operation: "UpdateService",
+                detail: "no service",
+            }))
+    }
+
+    /// Creates a task set of the service with the task definition, scaled in percent of
+    /// the desired count of the service.
+    async fn create_task_set(
+        &self,
+        cluster: &str,
+        service_conf: &config::ecs::Service,
+        task_definition_arn: &str,
+        scale: f64,
+    ) -> Result<rusoto_ecs::TaskSet, Box<dyn error::Error>> {
+        trace!("command::ecs::Executer::create_task_set");
+
+        let req = rusoto_ecs::CreateTaskSetRequest {
+            cluster: cluster.to_owned(),
+            service: service_conf.name.to_owned(),
+            task_definition: task_definition_arn.to_owned(),
+            load_balancers: service_conf
+                .load_balancers
                 .as_ref()
-                .map(|d| d.to_rusoto()),
+                .map(|lbs| lbs.iter().map(|lb| lb.to_rusoto()).collect()),
+            launch_type: service_conf.launch_type.to_owned(),
+            capacity_provider_strategy: service_conf
+                .capacity_provider_strategy
+                .as_ref()
+                .map(|cps| cps.iter().map(|cp| cp.to_rusoto()).collect()),
             network_configuration: service_conf
                 .network_configuration
                 .as_ref()
                 .map(|e| e.to_rusoto()),
-            task_definition: task_definition.task_definition_arn.to_owned(),
+            service_registries: service_conf
+                .service_registries
+                .as_ref()
+                .map(|srs| srs.iter().map(|sr| sr.to_rusoto()).collect()),
             platform_version: service_conf.platform_version.to_owned(),
-            enable_execute_command: service_conf.enable_execute_command,
+            scale: Some(percent(scale)),
             ..Default::default()
         };
 
-        let res = self.ecs_client().update_service(req).await?;
-        info!("Completed to update service successfully");
+        if !self.mutable("ECS CreateTaskSet", &req) {
+            return Ok(rusoto_ecs::TaskSet {
+                id: Some(String::from("<new-task-set>")),
+                task_definition: Some(req.task_definition),
+                scale: req.scale,
+                ..Default::default()
+            });
+        }
 
-        let service = res.service.map(|s| s.to_owned());
-        service.ok_or(Box::new(CommandError::Unknown))
+        let res = self
+            .ecs_client()
+            .create_task_set(req)
+            .await
+            .map_err(|e| CommandError::aws_api("ECS", "CreateTaskSet", e))?;
+        info!("Completed to create task set successfully");
+
+        res.task_set
+            .ok_or(Box::new(CommandError::UnexpectedResponse {
+                operation: "CreateTaskSet",
+                detail: "no task set",
+            }))
     }
 
-    async fn describe_task(
+    async fn update_task_set_scale(
         &self,
         cluster: &str,
-        task_arn: &str,
-    ) -> Result<TaskDescription, Box<dyn error::Error>> {
-        let req = rusoto_ecs::DescribeTasksRequest {
-            cluster: Some(cluster.to_owned()),
-            tasks: vec![task_arn.to_owned()],
-            ..Default::default()
+        service_name: &str,
+        task_set: &str,
+        scale: f64,
+    ) -> Result<(), Box<dyn error::Error>> {
+        trace!("command::ecs::Executer::update_task_set_scale");
+
+        let req = rusoto_ecs::UpdateTaskSetRequest {
+            cluster: cluster.to_owned(),
+            service: service_name.to_owned(),
+            task_set: task_set.to_owned(),
+            scale: percent(scale),
         };
 
-        let result = self.ecs_client().describe_tasks(req).await?;
-        debug!("{:?}", result);
+        if !self.mutable("ECS UpdateTaskSet", &req) {
+            return Ok(());
+        }
 
-        let failure = result
-            .failures
-            .as_ref()
-            .and_then(|failures| failures.first())
-            .map(|f| f.to_owned());
-        match result.tasks.as_ref().and_then(|tasks| tasks.first()) {
-            Some(task) => Ok(TaskDescription {
-                task: Some(task.to_owned()),
-                failure: failure,
-            }),
-            None => Err(Box::new(CommandError::Unknown)),
+        self.ecs_client()
+            .update_task_set(req)
+            .await
+            .map_err(|e| CommandError::aws_api("ECS", "UpdateTaskSet", e))?;
+        info!("Completed to update task set successfully");
+        Ok(())
+    }
+
+    async fn update_service_primary_task_set(
+        &self,
+        cluster: &str,
+        service_name: &str,
+        task_set: &str,
+    ) -> Result<(), Box<dyn error::Error>> {
+        trace!("command::ecs::Executer::update_service_primary_task_set");
+
+        let req = rusoto_ecs::UpdateServicePrimaryTaskSetRequest {
+            cluster: cluster.to_owned(),
+            service: service_name.to_owned(),
+            primary_task_set: task_set.to_owned(),
+        };
+
+        if !self.mutable("ECS UpdateServicePrimaryTaskSet", &req) {
+            return Ok(());
         }
+
+        self.ecs_client()
+            .update_service_primary_task_set(req)
+            .await
+            .map_err(|e| CommandError::aws_api("ECS", "UpdateServicePrimaryTaskSet", e))?;
+        info!("Completed to update primary task set successfully");
+        Ok(())
     }
 
-    async fn run_task(
+    async fn delete_task_set(
         &self,
         cluster: &str,
-        task_definition_arn: &str,
-        launch_type: Option<&str>,
-        network_configuration: Option<&config::ecs::NetworkConfiguration>,
-        platform_version: Option<&str>,
-        enable_execute_command: Option<bool>,
-    ) -> Result<TaskDescription, Box<dyn error::Error>> {
-        let req = rusoto_ecs::RunTaskRequest {
-            cluster: Some(cluster.to_owned()),
-            task_definition: task_definition_arn.to_owned(),
-            launch_type: launch_type.map(str::to_string),
-            network_configuration: network_configuration.map(|d| d.to_rusoto()),
-            platform_version: platform_version.map(str::to_string),
-            enable_execute_command: enable_execute_command,
-            ..Default::default()
+        service_name: &str,
+        task_set: &str,
+    ) -> Result<(), Box<dyn error::Error>> {
+        trace!("command::ecs::Executer::delete_task_set");
+
+        let req = rusoto_ecs::DeleteTaskSetRequest {
+            cluster: cluster.to_owned(),
+            service: service_name.to_owned(),
+            task_set: task_set.to_owned(),
+            force: None,
         };
 
-        let result = self.ecs_client().run_task(req).await?;
-        info!("Completed to run task successfully");
+        if !self.mutable("ECS DeleteTaskSet", &req) {
+            return Ok(());
+        }
 
-        debug!("{:?}", result);
+        self.ecs_client()
+            .delete_task_set(req)
+            .await
+            .map_err(|e| CommandError::aws_api("ECS", "DeleteTaskSet", e))?;
+        info!("Completed to delete task set successfully");
+        Ok(())
+    }
 
-        let failure = result
-            .failures
-            .as_ref()
-            .and_then(|failures| failures.first())
-            .map(|f| f.to_owned());
-        match result.tasks.as_ref().and_then(|tasks| tasks.first()) {
-            Some(task) => Ok(TaskDescription {
-                task: Some(task.to_owned()),
-                failure: failure,
-            }),
-            None => Err(Box::new(CommandError::Unknown)),
+    async fn list_service_tasks(
+        &self,
+        cluster: &str,
+        service_name: &str,
+        desired_status: &str,
+    ) -> Result<Vec<String>, Box<dyn error::Error>> {
+        trace!("command::ecs::Executer::list_service_tasks");
+
+        let mut task_arns = Vec::new();
+        let mut next_token = None;
+        loop {
+            let req = rusoto_ecs::ListTasksRequest {
+                cluster: Some(cluster.to_owned()),
+                service_name: Some(service_name.to_owned()),
+                desired_status: Some(desired_status.to_owned()),
+                next_token,
+                ..Default::default()
+            };
+
+            let res = self
+                .ecs_client()
+                .list_tasks(req)
+                .await
+                .map_err(|e| CommandError::aws_api("ECS", "ListTasks", e))?;
+            info!("Completed to list tasks successfully");
+
+            if let Some(page) = res.task_arns {
+                task_arns.extend(page);
+            }
+
+            next_token = res.next_token;
+            if next_token.is_none() {
+                break;
+            }
         }
+
+        Ok(task_arns)
+    }
+
+    async fn describe_tasks(
+        &self,
+        cluster: &str,
+        task_arns: &[String],
+    ) -> Result<Vec<rusoto_ecs::Task>, Box<dyn error::Error>> {
+        trace!("command::ecs::Executer::describe_tasks");
+
+        let mut tasks = Vec::new();
+        // DescribeTasks accepts up to 100 tasks at once.
+        for chunk in task_arns.chunks(100) {
+            let req = rusoto_ecs::DescribeTasksRequest {
+                cluster: Some(cluster.to_owned()),
+                tasks: chunk.to_vec(),
+                ..Default::default()
+            };
+
+            let res = self
+                .ecs_client()
+                .describe_tasks(req)
+                .await
+                .map_err(|e| CommandError::aws_api("ECS", "DescribeTasks", e))?;
+            info!("Completed to describe tasks successfully");
+
+            if let Some(page) = res.tasks {
+                tasks.extend(page);
+            }
+        }
+
+        Ok(tasks)
+    }
+
+    /// Runs tasks of the entry, in batches of 10 which RunTask accepts at once.
+    /// Tasks which could not be started are returned as failures.
+    async fn run_task(
+        &self,
+        run_task_conf: &config::command::RunTaskConfig,
+        task_definition_arn: &str,
+        overrides: Option<&config::ecs::TaskOverride>,
+        count: i64,
+    ) -> Result<Vec<TaskDescription>, Box<dyn error::Error>> {
+        trace!("command::ecs::Executer::run_task");
+
+        let mut descriptions = Vec::new();
+        let mut remaining = count;
+        while remaining > 0 {
+            let batch = remaining.min(10);
+            remaining -= batch;
+
+            let req = rusoto_ecs::RunTaskRequest {
+                cluster: Some(run_task_conf.cluster.to_owned()),
+                task_definition: task_definition_arn.to_owned(),
+                count: Some(batch),
+                launch_type: run_task_conf.launch_type.to_owned(),
+                capacity_provider_strategy: run_task_conf
+                    .capacity_provider_strategy
+                    .as_ref()
+                    .map(|cps| cps.iter().map(|cp| cp.to_rusoto()).collect()),
+                placement_constraints: run_task_conf
+                    .placement_constraints
+                    .as_ref()
+                    .map(|pcs| pcs.iter().map(|pc| pc.to_rusoto()).collect()),
+                placement_strategy: run_task_conf
+                    .placement_strategy
+                    .as_ref()
+                    .map(|pss| pss.iter().map(|ps| ps.to_rusoto()).collect()),
+                network_configuration: run_task_conf
+                    .network_configuration
+                    .as_ref()
+                    .map(|d| d.to_rusoto()),
+                platform_version: run_task_conf.platform_version.to_owned(),
+                enable_execute_command: run_task_conf.enable_execute_command,
+                started_by: run_task_conf.started_by.to_owned(),
+                group: run_task_conf.group.to_owned(),
+                tags: run_task_conf
+                    .tags
+                    .as_ref()
+                    .map(|tags| tags.iter().map(|t| t.to_rusoto()).collect()),
+                propagate_tags: run_task_conf.propagate_tags.to_owned(),
+                overrides: overrides.map(|o| o.to_rusoto()),
+                ..Default::default()
+            };
+
+            if !self.mutable("ECS RunTask", &req) {
+                continue;
+            }
+
+            let result = self
+                .ecs_client()
+                .run_task(req)
+                .await
+                .map_err(|e| CommandError::aws_api("ECS", "RunTask", e))?;
+            info!("Completed to run task successfully");
+
+            debug!("{:?}", result);
+
+            for task in result.tasks.unwrap_or_default() {
+                descriptions.push(TaskDescription {
+                    task: Some(task),
+                    failure: None,
+                });
+            }
+            for failure in result.failures.unwrap_or_default() {
+                descriptions.push(TaskDescription {
+                    task: None,
+                    failure: Some(failure),
+                });
+            }
+        }
+
+        if !self.dry_run() && descriptions.iter().all(|d| d.task.is_none()) {
+            return Err(Box::new(CommandError::TaskFailed {
+                exit_code: None,
+                reason: descriptions
+                    .into_iter()
+                    .find_map(|d| d.failure.and_then(|f| f.reason))
+                    .unwrap_or_else(|| String::from("The task could not be started")),
+            }));
+        }
+
+        Ok(descriptions)
     }
 
     fn detect_task_definition_changes(
         &self,
         task_definition_conf: &config::ecs::TaskDefinition,
-        current_task_definitions: &rusoto_ecs::TaskDefinition,
+        current_task_definition: &rusoto_ecs::TaskDefinition,
     ) -> bool {
-        if current_task_definitions.family.is_none()
-            || current_task_definitions.family.as_ref().unwrap().as_str()
-                != task_definition_conf.family.as_str()
-        {
-            return true;
-        }
-        // if current_task_definitions.task_role_arn.is_none() || current_task_definitions.task_role_arn.as_ref().unwrap().as_str() != task_definition_conf.task_role_arn.as_str() {
-        //   return true
-        // }
-        // if current_task_definitions.network_mode.is_none() || current_task_definitions.network_mode.as_ref().unwrap().as_str() != task_definition_conf.network_mode.as_str() {
-        //   return true
-        // }
+        let desired = task_definition::normalize(&task_definition_conf.to_rusoto());
+        let current =
+            task_definition::normalize(&task_definition::to_request(current_task_definition));
 
-        // TODO: detect difference between config of container definition and current one
-        true
+        desired != current
+    }
+}
+
+fn percent(value: f64) -> rusoto_ecs::Scale {
+    rusoto_ecs::Scale {
+        unit: Some(String::from("PERCENT")),
+        value: Some(value),
+    }
+}
+
+/// Builds the request of UpdateService with the settings of the service in config.
+pub(super) fn update_service_request(
+    cluster: &str,
+    service_conf: &config::ecs::Service,
+    task_definition: &rusoto_ecs::TaskDefinition,
+    force_new_deployment: bool,
+) -> Result<rusoto_ecs::UpdateServiceRequest, Box<dyn error::Error>> {
+    if task_definition.task_definition_arn.is_none() {
+        return Err(Box::new(CommandError::TaskDefinitionNotFound(
+            task_definition.family.to_owned().unwrap_or_default(),
+        )));
+    }
 
-        //false
+    let mut req = rusoto_ecs::UpdateServiceRequest {
+        service: service_conf.name.to_owned(),
+        cluster: Some(cluster.to_owned()),
+        desired_count: service_conf.desired_count,
+        deployment_configuration: service_conf
+            .deployment_configuration
+            .as_ref()
+            .map(|d| d.to_rusoto()),
+        capacity_provider_strategy: service_conf
+            .capacity_provider_strategy
+            .as_ref()
+            .map(|cps| cps.iter().map(|cp| cp.to_rusoto()).collect()),
+        placement_constraints: service_conf
+            .placement_constraints
+            .as_ref()
+            .map(|pcs| pcs.iter().map(|pc| pc.to_rusoto()).collect()),
+        placement_strategy: service_conf
+            .placement_strategy
+            .as_ref()
+            .map(|pss| pss.iter().map(|ps| ps.to_rusoto()).collect()),
+        network_configuration: service_conf
+            .network_configuration
+            .as_ref()
+            .map(|e| e.to_rusoto()),
+        task_definition: task_definition.task_definition_arn.to_owned(),
+        platform_version: service_conf.platform_version.to_owned(),
+        enable_execute_command: service_conf.enable_execute_command,
+        health_check_grace_period_seconds: service_conf.health_check_grace_period_seconds,
+        force_new_deployment: if force_new_deployment {
+            Some(true)
+        } else {
+            None
+        },
+        ..Default::default()
+    };
+
+    // Services deployed by CodeDeploy take a new task definition through a deployment of it,
+    // and those of the EXTERNAL controller through a task set,
+    // so UpdateService is only allowed to change the other settings.
+    if service_conf.uses_code_deploy() || service_conf.uses_external_controller() {
+        req = rusoto_ecs::UpdateServiceRequest {
+            service: req.service,
+            cluster: req.cluster,
+            desired_count: req.desired_count,
+            deployment_configuration: req
+                .deployment_configuration
+                .filter(|_| service_conf.uses_code_deploy()),
+            placement_constraints: req.placement_constraints,
+            placement_strategy: req.placement_strategy,
+            health_check_grace_period_seconds: req.health_check_grace_period_seconds,
+            ..Default::default()
+        };
     }
+
+    Ok(req)
 }
@@ -0,0 +1,177 @@
+use async_trait::async_trait;
+use std::error;
+
+use rusoto_ecs;
+use rusoto_ecs::Ecs;
+use serde_json::Value;
+
+use super::executer::{update_service_request, Executer};
+use crate::command::aws::JsonClient;
+use crate::command::error::CommandError;
+use crate::config;
+
+/// Requests sent in JSON, for the fields which the SDK neither sends nor returns,
+/// such as `runtimePlatform` of task definitions.
+#[async_trait]
+pub trait JsonExecuter: Executer {
+    fn ecs_json_client(&self) -> &JsonClient;
+
+    /// Describes a task definition given by its family, `family:revision` or ARN,
+    /// with its `runtimePlatform` if any.
+    async fn describe_task_definition_json(
+        &self,
+        task_definition: &str,
+    ) -> Result<Option<(rusoto_ecs::TaskDefinition, Option<Value>)>, Box<dyn error::Error>> {
+        trace!("command::ecs::JsonExecuter::describe_task_definition_json");
+
+        let res = self
+            .ecs_json_client()
+            .request_or_none(
+                "DescribeTaskDefinition",
+                &json!({ "taskDefinition": task_definition }),
+                "ClientException",
+            )
+            .await?;
+        let mut res = match res {
+            Some(res) => res,
+            None => {
+                info!("Not found the task-definition: {}", task_definition);
+                return Ok(None);
+            }
+        };
+        info!("Completed to describe task_definition successfully");
+
+        let runtime_platform =
+            Some(res["taskDefinition"]["runtimePlatform"].take()).filter(|v| !v.is_null());
+        let task_definition = serde_json::from_value(res["taskDefinition"].take())?;
+        Ok(Some((task_definition, runtime_platform)))
+    }
+
+    async fn register_task_definition(
+        &self,
+        task_definition_conf: &config::ecs::TaskDefinition,
+    ) -> Result<rusoto_ecs::TaskDefinition, Box<dyn error::Error>> {
+        trace!("command::ecs::JsonExecuter::register_task_definition");
+        let req = task_definition_conf.to_rusoto();
+
+        // The SDK cannot send `runtimePlatform`, so the request with it is sent in JSON.
+        let json_req = match task_definition_conf.runtime_platform.as_ref() {
+            Some(runtime_platform) => {
+                let mut json_req = serde_json::to_value(&req)?;
+                json_req["runtimePlatform"] = runtime_platform.to_json();
+                Some(json_req)
+            }
+            None => None,
+        };
+
+        let mutable = match json_req.as_ref() {
+            Some(json_req) => self.mutable("ECS RegisterTaskDefinition", json_req),
+            None => self.mutable("ECS RegisterTaskDefinition", &req),
+        };
+        if !mutable {
+            return Ok(rusoto_ecs::TaskDefinition {
+                task_definition_arn: Some(format!("{}:<new-revision>", req.family)),
+                family: Some(req.family),
+                container_definitions: Some(req.container_definitions),
+                ..Default::default()
+            });
+        }
+
+        let task_definition = match json_req {
+            Some(json_req) => {
+                let mut res = self
+                    .ecs_json_client()
+                    .request("RegisterTaskDefinition", &json_req)
+                    .await?;
+                serde_json::from_value(res["taskDefinition"].take())?
+            }
+            None => {
+                self.ecs_client()
+                    .register_task_definition(req)
+                    .await
+                    .map_err(|e| CommandError::aws_api("ECS", "RegisterTaskDefinition", e))?
+                    .task_definition
+            }
+        };
+        info!("Completed to register task_definition successfully");
+
+        task_definition.ok_or(Box::new(CommandError::UnexpectedResponse {
+            operation: "RegisterTaskDefinition",
+            detail: "no task definition",
+        }))
+    }
+
+    /// Updates the service as `update_service`, also with the settings which UpdateService
+    /// accepts but the SDK cannot send, such as `loadBalancers`.
+    async fn update_service_json(
+        &self,
+        cluster: &str,
+        service_conf: &config::ecs::Service,
+        task_definition: &rusoto_ecs::TaskDefinition,
+        force_new_deployment: bool,
+    ) -> Result<rusoto_ecs::Service, Box<dyn error::Error>> {
+        trace!("command::ecs::JsonExecuter::update_service_json");
+
+        let fields = update_service_json_fields(service_conf)?;
+        if fields.is_empty() {
+            return self
+                .update_service(cluster, service_conf, task_definition, force_new_deployment)
+                .await;
+        }
+
+        let req =
+            update_service_request(cluster, service_conf, task_definition, force_new_deployment)?;
+        let mut json_req = serde_json::to_value(&req)?;
+        for (key, value) in fields.into_iter() {
+            json_req[key] = value;
+        }
+
+        if !self.mutable("ECS UpdateService", &json_req) {
+            return Ok(rusoto_ecs::Service {
+                service_name: Some(req.service),
+                desired_count: req.desired_count,
+                task_definition: req.task_definition,
+                ..Default::default()
+            });
+        }
+
+        let mut res = self
+            .ecs_json_client()
+            .request("UpdateService", &json_req)
+            .await?;
+        info!("Completed to update service successfully");
+
+        let service: Option<rusoto_ecs::Service> = serde_json::from_value(res["service"].take())?;
+        service.ok_or(Box::new(CommandError::UnexpectedResponse {
+            operation: "UpdateService",
+            detail: "no service",
+        }))
+    }
+}
+
+/// Lists the fields of UpdateService in config which the SDK lacks.
+/// Load balancers and service registries of the services deployed by CodeDeploy
+/// or the EXTERNAL controller are given to their task sets instead.
+fn update_service_json_fields(
+    service_conf: &config::ecs::Service,
+) -> Result<Vec<(&'static str, Value)>, Box<dyn error::Error>> {
+    let mut fields = Vec::new();
+    if !service_conf.uses_code_deploy() && !service_conf.uses_external_controller() {
+        if let Some(lbs) = service_conf.load_balancers.as_ref() {
+            let lbs: Vec<rusoto_ecs::LoadBalancer> = lbs.iter().map(|lb| lb.to_rusoto()).collect();
+            fields.push(("loadBalancers", serde_json::to_value(&lbs)?));
+        }
+        if let Some(srs) = service_conf.service_registries.as_ref() {
+            let srs: Vec<rusoto_ecs::ServiceRegistry> =
+                srs.iter().map(|sr| sr.to_rusoto()).collect();
+            fields.push(("serviceRegistries", serde_json::to_value(&srs)?));
+        }
+    }
+    if let Some(propagate_tags) = service_conf.propagate_tags.as_ref() {
+        fields.push(("propagateTags", json!(propagate_tags)));
+    }
+    if let Some(enable_ecs_managed_tags) = service_conf.enable_ecs_managed_tags {
+        fields.push(("enableECSManagedTags", json!(enable_ecs_managed_tags)));
+    }
+    Ok(fields)
+}
@@ -1,3 +1,11 @@
+mod diff;
 mod executer;
+mod json_executer;
+mod task_definition;
 
+pub use self::diff::{
+    recreation_changes, runtime_platform_change, service_changes, task_definition_changes, Change,
+};
 pub use self::executer::{Executer, TaskDescription};
+pub use self::json_executer::JsonExecuter;
+pub use self::task_definition::revision_of;
@@ -0,0 +1,311 @@
+use rusoto_ecs;
+use serde_json::Value;
+
+// Normalization of task definitions.
+// ECS fills in defaults and reorders some values of a registered task definition,
+// so a config and the registered one are compared after both are normalized.
+
+const DEFAULT_NETWORK_MODE: &str = "bridge";
+const DEFAULT_PROTOCOL: &str = "tcp";
+const DEFAULT_CPU_ARCHITECTURE: &str = "X86_64";
+const DEFAULT_OPERATING_SYSTEM_FAMILY: &str = "LINUX";
+
+const DEFAULT_HEALTH_CHECK_INTERVAL: i64 = 30;
+const DEFAULT_HEALTH_CHECK_RETRIES: i64 = 3;
+const DEFAULT_HEALTH_CHECK_TIMEOUT: i64 = 5;
+
+/// Builds the request which would register the given task definition.
+pub fn to_request(
+    task_definition: &rusoto_ecs::TaskDefinition,
+) -> rusoto_ecs::RegisterTaskDefinitionRequest {
+    rusoto_ecs::RegisterTaskDefinitionRequest {
+        family: task_definition.family.to_owned().unwrap_or_default(),
+        task_role_arn: task_definition.task_role_arn.to_owned(),
+        network_mode: task_definition.network_mode.to_owned(),
+        volumes: task_definition.volumes.to_owned(),
+        container_definitions: task_definition
+            .container_definitions
+            .to_owned()
+            .unwrap_or_default(),
+        execution_role_arn: task_definition.execution_role_arn.to_owned(),
+        requires_compatibilities: task_definition.requires_compatibilities.to_owned(),
+        cpu: task_definition.cpu.to_owned(),
+        memory: task_definition.memory.to_owned(),
+        proxy_configuration: task_definition.proxy_configuration.to_owned(),
+        placement_constraints: task_definition.placement_constraints.to_owned(),
+        pid_mode: task_definition.pid_mode.to_owned(),
+        ipc_mode: task_definition.ipc_mode.to_owned(),
+        ephemeral_storage: task_definition.ephemeral_storage.to_owned(),
+        inference_accelerators: task_definition.inference_accelerators.to_owned(),
+        ..Default::default()
+    }
+}
+
+pub fn normalize(
+    req: &rusoto_ecs::RegisterTaskDefinitionRequest,
+) -> rusoto_ecs::RegisterTaskDefinitionRequest {
+    let mut req = req.to_owned();
+
+    let network_mode = req
+        .network_mode
+        .to_owned()
+        .unwrap_or_else(|| DEFAULT_NETWORK_MODE.to_owned());
+
+    req.container_definitions = req
+        .container_definitions
+        .iter()
+        .map(|cd| normalize_container_definition(cd, &network_mode))
+        .collect();
+    req.container_definitions
+        .sort_by(|a, b| a.name.cmp(&b.name));
+
+    req.network_mode = Some(network_mode);
+    req.volumes = non_empty(req.volumes).map(|mut volumes| {
+        for volume in volumes.iter_mut() {
+            volume.host = volume.host.take().filter(|h| h.source_path.is_some());
+        }
+        volumes.sort_by(|a, b| a.name.cmp(&b.name));
+        volumes
+    });
+    req.requires_compatibilities = sorted(non_empty(req.requires_compatibilities));
+    req.placement_constraints = non_empty(req.placement_constraints);
+    req.inference_accelerators = non_empty(req.inference_accelerators);
+    // Tags are not a part of a registered task definition, so they are not compared.
+    req.tags = None;
+    req.proxy_configuration = req.proxy_configuration.map(|mut pc| {
+        pc.properties = non_empty(pc.properties).map(|mut properties| {
+            properties.sort_by(|a, b| a.name.cmp(&b.name));
+            properties
+        });
+        pc
+    });
+
+    req
+}
+
+fn normalize_container_definition(
+    container_definition: &rusoto_ecs::ContainerDefinition,
+    network_mode: &str,
+) -> rusoto_ecs::ContainerDefinition {
+    let mut cd = container_definition.to_owned();
+
+    cd.cpu = cd.cpu.or(Some(0));
+    cd.essential = cd.essential.or(Some(true));
+
+    cd.command = non_empty(cd.command);
+    cd.entry_point = non_empty(cd.entry_point);
+    cd.dns_search_domains = non_empty(cd.dns_search_domains);
+    cd.dns_servers = non_empty(cd.dns_servers);
+    cd.docker_security_options = non_empty(cd.docker_security_options);
+    cd.links = non_empty(cd.links);
+    cd.extra_hosts = non_empty(cd.extra_hosts);
+    cd.environment_files = non_empty(cd.environment_files);
+    cd.resource_requirements = non_empty(cd.resource_requirements);
+    cd.system_controls = non_empty(cd.system_controls);
+    cd.ulimits = non_empty(cd.ulimits);
+    cd.depends_on = non_empty(cd.depends_on);
+    cd.docker_labels = cd.docker_labels.filter(|labels| !labels.is_empty());
+
+    cd.environment = non_empty(cd.environment).map(|mut environment| {
+        environment.sort_by(|a, b| a.name.cmp(&b.name));
+        environment
+    });
+    cd.secrets = non_empty(cd.secrets).map(|mut secrets| {
+        secrets.sort_by(|a, b| a.name.cmp(&b.name));
+        secrets
+    });
+    cd.port_mappings = non_empty(cd.port_mappings).map(|port_mappings| {
+        port_mappings
+            .into_iter()
+            .map(|pm| normalize_port_mapping(pm, network_mode))
+            .collect()
+    });
+    cd.mount_points = non_empty(cd.mount_points).map(|mount_points| {
+        mount_points
+            .into_iter()
+            .map(|mut mp| {
+                mp.read_only = mp.read_only.or(Some(false));
+                mp
+            })
+            .collect()
+    });
+    cd.volumes_from = non_empty(cd.volumes_from).map(|volumes_from| {
+        volumes_from
+            .into_iter()
+            .map(|mut vf| {
+                vf.read_only = vf.read_only.or(Some(false));
+                vf
+            })
+            .collect()
+    });
+    cd.log_configuration = cd.log_configuration.map(|mut lc| {
+        lc.options = lc.options.filter(|options| !options.is_empty());
+        lc.secret_options = non_empty(lc.secret_options);
+        lc
+    });
+    cd.health_check = cd.health_check.map(|mut hc| {
+        hc.interval = hc.interval.or(Some(DEFAULT_HEALTH_CHECK_INTERVAL));
+        hc.retries = hc.retries.or(Some(DEFAULT_HEALTH_CHECK_RETRIES));
+        hc.timeout = hc.timeout.or(Some(DEFAULT_HEALTH_CHECK_TIMEOUT));
+        hc.start_period = hc.start_period.filter(|p| *p != 0);
+        hc
+    });
+
+    cd
+}
+
+fn normalize_port_mapping(
+    mut port_mapping: rusoto_ecs::PortMapping,
+    network_mode: &str,
+) -> rusoto_ecs::PortMapping {
+    port_mapping.protocol = port_mapping
+        .protocol
+        .or_else(|| Some(DEFAULT_PROTOCOL.to_owned()));
+
+    // In awsvpc and host network mode, the host port is always the same as the container port.
+    // In bridge mode, zero means a dynamically assigned one.
+    let fixed_host_port = network_mode == "awsvpc" || network_mode == "host";
+    if port_mapping.host_port == Some(0)
+        || (fixed_host_port && port_mapping.host_port == port_mapping.container_port)
+    {
+        port_mapping.host_port = None;
+    }
+
+    port_mapping
+}
+
+/// Returns the revision in an ARN of a task definition, which ends with `:revision`.
+pub fn revision_of(task_definition_arn: &str) -> i64 {
+    task_definition_arn
+        .rsplit(':')
+        .next()
+        .and_then(|r| r.parse().ok())
+        .unwrap_or(0)
+}
+
+fn non_empty<T>(list: Option<Vec<T>>) -> Option<Vec<T>> {
+    list.filter(|l| !l.is_empty())
+}
+
+fn sorted<T: Ord>(list: Option<Vec<T>>) -> Option<Vec<T>> {
+    list.map(|mut l| {
+        l.sort();
+        l
+    })
+}
+
+/// Fills in the defaults of `runtimePlatform`, which ECS assumes when it is not given.
+pub fn normalize_runtime_platform(runtime_platform: Option<Value>) -> Value {
+    let mut runtime_platform = runtime_platform.unwrap_or_else(|| json!({}));
+    if runtime_platform["cpuArchitecture"].is_null() {
+        runtime_platform["cpuArchitecture"] = json!(DEFAULT_CPU_ARCHITECTURE);
+    }
+    if runtime_platform["operatingSystemFamily"].is_null() {
+        runtime_platform["operatingSystemFamily"] = json!(DEFAULT_OPERATING_SYSTEM_FAMILY);
+    }
+    runtime_platform
+}
+
+#[test]
+fn test_normalize_filled_defaults() {
+    let conf = rusoto_ecs::RegisterTaskDefinitionRequest {
+        family: String::from("test"),
+        container_definitions: vec![rusoto_ecs::ContainerDefinition {
+            name: Some(String::from("test")),
+            image: Some(String::from("test.dkr.com/racco/test:latest")),
+            port_mappings: Some(vec![rusoto_ecs::PortMapping {
+                container_port: Some(80),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    let registered = rusoto_ecs::RegisterTaskDefinitionRequest {
+        family: String::from("test"),
+        network_mode: Some(String::from("bridge")),
+        container_definitions: vec![rusoto_ecs::ContainerDefinition {
+            name: Some(String::from("test")),
+            image: Some(String::from("test.dkr.com/racco/test:latest")),
+            cpu: Some(0),
+            essential: Some(true),
+            environment: Some(vec![]),
+            mount_points: Some(vec![]),
+            volumes_from: Some(vec![]),
+            port_mappings: Some(vec![rusoto_ecs::PortMapping {
+                container_port: Some(80),
+                host_port: Some(0),
+                protocol: Some(String::from("tcp")),
+            }]),
+            ..Default::default()
+        }],
+        volumes: Some(vec![]),
+        ..Default::default()
+    };
+
+    assert_eq!(normalize(&conf), normalize(&registered));
+}
+
+#[test]
+fn test_normalize_environment_order() {
+    let env = |name: &str, value: &str| rusoto_ecs::KeyValuePair {
+        name: Some(name.to_owned()),
+        value: Some(value.to_owned()),
+    };
+    let task_definition =
+        |environment: Vec<rusoto_ecs::KeyValuePair>| rusoto_ecs::RegisterTaskDefinitionRequest {
+            family: String::from("test"),
+            container_definitions: vec![rusoto_ecs::ContainerDefinition {
+                name: Some(String::from("test")),
+                environment: Some(environment),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+    let a = task_definition(vec![env("FOO", "1"), env("BAR", "2")]);
+    let b = task_definition(vec![env("BAR", "2"), env("FOO", "1")]);
+    let c = task_definition(vec![env("BAR", "2"), env("FOO", "3")]);
+
+    assert_eq!(normalize(&a), normalize(&b));
+    assert_ne!(normalize(&a), normalize(&c));
+}
+
+#[test]
+fn test_normalize_image_change() {
+    let task_definition = |image: &str| rusoto_ecs::RegisterTaskDefinitionRequest {
+        family: String::from("test"),
+        container_definitions: vec![rusoto_ecs::ContainerDefinition {
+            name: Some(String::from("test")),
+            image: Some(image.to_owned()),
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+
+    assert_ne!(
+        normalize(&task_definition("test.dkr.com/racco/test:v1")),
+        normalize(&task_definition("test.dkr.com/racco/test:v2"))
+    );
+}
+
+#[test]
+fn test_revision_of() {
+    assert_eq!(
+        revision_of("arn:aws:ecs:ap-northeast-1:123456789012:task-definition/racco-web:12"),
+        12
+    );
+    assert_eq!(revision_of("racco-web"), 0);
+}
+
+#[test]
+fn test_normalize_runtime_platform() {
+    assert_eq!(
+        normalize_runtime_platform(None),
+        normalize_runtime_platform(Some(json!({"cpuArchitecture": "X86_64"})))
+    );
+    assert_ne!(
+        normalize_runtime_platform(None),
+        normalize_runtime_platform(Some(json!({"cpuArchitecture": "ARM64"})))
+    );
+}
@@ -1,16 +1,207 @@
 use std::error;
 use std::fmt;
+use std::time::Duration;
 
 #[derive(Debug)]
 pub enum CommandError {
     CommandNotFound,
+    InvalidArgument(String),
+    ConfigEntryNotFound {
+        section: &'static str,
+        name: String,
+    },
+    NoEntryMatched {
+        section: &'static str,
+        selector: String,
+    },
+    ClusterNotFound(String),
+    ServiceNotFound {
+        cluster: String,
+        name: String,
+    },
+    TaskDefinitionNotFound(String),
+    DeploymentNotFound(String),
+    InvalidParameterName(String),
+    ProcessExited(Option<i32>),
+    TaskFailed {
+        exit_code: Option<i64>,
+        reason: String,
+    },
+    ContainerExited {
+        container: String,
+        exit_code: i64,
+    },
+    DeploymentFailed(String),
+    HookFailed {
+        phase: &'static str,
+        hook: String,
+        source: Box<dyn error::Error>,
+    },
+    Timeout(Duration),
+    DriftDetected,
+    AwsApi {
+        service: &'static str,
+        operation: &'static str,
+        source: Box<dyn error::Error + Send + Sync>,
+    },
+    UnexpectedResponse {
+        operation: &'static str,
+        detail: &'static str,
+    },
     Unknown,
 }
 
+impl CommandError {
+    pub fn aws_api<E: error::Error + Send + Sync + 'static>(
+        service: &'static str,
+        operation: &'static str,
+        source: E,
+    ) -> CommandError {
+        CommandError::AwsApi {
+            service,
+            operation,
+            source: Box::new(source),
+        }
+    }
+
+    /// Finds a `CommandError` in a boxed error, which may be boxed twice by `?`.
+    pub fn find<'a>(err: &'a (dyn error::Error + 'static)) -> Option<&'a CommandError> {
+        err.downcast_ref::<CommandError>().or_else(|| {
+            err.downcast_ref::<Box<CommandError>>()
+                .map(|boxed| boxed.as_ref())
+        })
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match *self {
+            CommandError::CommandNotFound => "command_not_found",
+            CommandError::InvalidArgument(_) => "invalid_argument",
+            CommandError::ConfigEntryNotFound { .. } => "config_entry_not_found",
+            CommandError::NoEntryMatched { .. } => "no_entry_matched",
+            CommandError::ClusterNotFound(_) => "cluster_not_found",
+            CommandError::ServiceNotFound { .. } => "service_not_found",
+            CommandError::TaskDefinitionNotFound(_) => "task_definition_not_found",
+            CommandError::DeploymentNotFound(_) => "deployment_not_found",
+            CommandError::InvalidParameterName(_) => "invalid_parameter_name",
+            CommandError::ProcessExited(_) => "process_exited",
+            CommandError::TaskFailed { .. } => "task_failed",
+            CommandError::ContainerExited { .. } => "container_exited",
+            CommandError::DeploymentFailed(_) => "deployment_failed",
+            // The failure of the task is what a hook fails with.
+            CommandError::HookFailed { ref source, .. } => CommandError::find(source.as_ref())
+                .map(|e| e.kind())
+                .unwrap_or("deployment_failed"),
+            CommandError::Timeout(_) => "timeout",
+            CommandError::DriftDetected => "drift_detected",
+            CommandError::AwsApi { .. } => "aws_api",
+            CommandError::UnexpectedResponse { .. } => "unexpected_response",
+            CommandError::Unknown => "unknown",
+        }
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        match *self {
+            CommandError::DriftDetected => 2,
+            CommandError::CommandNotFound
+            | CommandError::InvalidArgument(_)
+            | CommandError::ConfigEntryNotFound { .. }
+            | CommandError::NoEntryMatched { .. } => 3,
+            CommandError::ClusterNotFound(_)
+            | CommandError::ServiceNotFound { .. }
+            | CommandError::TaskDefinitionNotFound(_)
+            | CommandError::DeploymentNotFound(_)
+            | CommandError::InvalidParameterName(_) => 4,
+            CommandError::AwsApi { .. } | CommandError::UnexpectedResponse { .. } => 5,
+            CommandError::TaskFailed { .. } => 6,
+            CommandError::DeploymentFailed(_) => 7,
+            CommandError::HookFailed { ref source, .. } => CommandError::find(source.as_ref())
+                .map(|e| e.exit_code())
+                .unwrap_or(7),
+            CommandError::Timeout(_) => 124,
+            // The exit code of the program is passed through.
+            CommandError::ProcessExited(code) => code.unwrap_or(1),
+            // Codes which an exit status cannot hold are reported as a failed task.
+            CommandError::ContainerExited { exit_code, .. } if (1..=255).contains(&exit_code) => {
+                exit_code as i32
+            }
+            CommandError::ContainerExited { .. } => 6,
+            CommandError::Unknown => 1,
+        }
+    }
+}
+
 impl fmt::Display for CommandError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             CommandError::CommandNotFound => write!(f, "Unknown command"),
+            CommandError::InvalidArgument(ref reason) => write!(f, "Invalid argument: {}", reason),
+            CommandError::ConfigEntryNotFound { section, ref name } => {
+                write!(f, "No entry named `{}` in `{}` of config", name, section)
+            }
+            CommandError::NoEntryMatched {
+                section,
+                ref selector,
+            } => write!(
+                f,
+                "No entry in `{}` of config matches: {}",
+                section, selector
+            ),
+            CommandError::ClusterNotFound(ref name) => write!(f, "Cluster not found: {}", name),
+            CommandError::ServiceNotFound {
+                ref cluster,
+                ref name,
+            } => write!(f, "Service not found: {} (cluster: {})", name, cluster),
+            CommandError::TaskDefinitionNotFound(ref name) => {
+                write!(f, "Task definition not found: {}", name)
+            }
+            CommandError::DeploymentNotFound(ref name) => {
+                write!(f, "No deployment in progress: {}", name)
+            }
+            CommandError::InvalidParameterName(ref name) => {
+                write!(f, "Parameter is not under the configured path: {}", name)
+            }
+            CommandError::ProcessExited(Some(code)) => {
+                write!(f, "The program exited with status code: {}", code)
+            }
+            CommandError::ProcessExited(None) => {
+                write!(f, "The program was terminated by a signal")
+            }
+            CommandError::TaskFailed {
+                exit_code: Some(code),
+                ref reason,
+            } => write!(f, "Task failed with exit code {}: {}", code, reason),
+            CommandError::TaskFailed {
+                exit_code: None,
+                ref reason,
+            } => write!(f, "Task failed: {}", reason),
+            CommandError::ContainerExited {
+                ref container,
+                exit_code,
+            } => write!(
+                f,
+                "The container `{}` exited with status code: {}",
+                container, exit_code
+            ),
+            CommandError::DeploymentFailed(ref reason) => {
+                write!(f, "Deployment failed: {}", reason)
+            }
+            CommandError::HookFailed {
+                phase,
+                ref hook,
+                ref source,
+            } => write!(f, "`{}` hook `{}` failed: {}", phase, hook, source),
+            CommandError::Timeout(ref duration) => {
+                write!(f, "Timed out after {} seconds", duration.as_secs())
+            }
+            CommandError::DriftDetected => write!(f, "Differences from the config detected"),
+            CommandError::AwsApi {
+                service,
+                operation,
+                ref source,
+            } => write!(f, "{} {} failed: {}", service, operation, source),
+            CommandError::UnexpectedResponse { operation, detail } => {
+                write!(f, "Unexpected response of {}: {}", operation, detail)
+            }
             CommandError::Unknown => write!(f, "Unexpected error occurred"),
         }
     }
@@ -20,14 +211,64 @@ impl error::Error for CommandError {
     fn description(&self) -> &str {
         match *self {
             CommandError::CommandNotFound => "Unknown command",
+            CommandError::InvalidArgument(_) => "Invalid argument",
+            CommandError::ConfigEntryNotFound { .. } => "No such entry in config",
+            CommandError::NoEntryMatched { .. } => "No entry in config matches",
+            CommandError::ClusterNotFound(_) => "Cluster not found",
+            CommandError::ServiceNotFound { .. } => "Service not found",
+            CommandError::TaskDefinitionNotFound(_) => "Task definition not found",
+            CommandError::DeploymentNotFound(_) => "No deployment in progress",
+            CommandError::InvalidParameterName(_) => "Parameter is not under the configured path",
+            CommandError::ProcessExited(_) => "The program exited with non-zero status",
+            CommandError::TaskFailed { .. } => "Task failed",
+            CommandError::ContainerExited { .. } => "The container exited with non-zero status",
+            CommandError::DeploymentFailed(_) => "Deployment failed",
+            CommandError::HookFailed { .. } => "Hook failed",
+            CommandError::Timeout(_) => "Timed out",
+            CommandError::DriftDetected => "Differences from the config detected",
+            CommandError::AwsApi { .. } => "AWS API request failed",
+            CommandError::UnexpectedResponse { .. } => "Unexpected response",
             CommandError::Unknown => "Unexpected error occurred",
         }
     }
 
     fn cause(&self) -> Option<&dyn error::Error> {
         match *self {
-            CommandError::CommandNotFound => None,
-            CommandError::Unknown => None,
+            CommandError::AwsApi { ref source, .. } => Some(source.as_ref()),
+            CommandError::HookFailed { ref source, .. } => Some(source.as_ref()),
+            _ => None,
         }
     }
 }
+
+#[test]
+fn test_find_boxed_twice() {
+    let err: Box<dyn error::Error> = Box::new(Box::new(CommandError::DriftDetected));
+    assert_eq!(
+        CommandError::find(err.as_ref()).map(|e| e.exit_code()),
+        Some(2)
+    );
+}
+
+#[test]
+fn test_hook_failed_keeps_the_task_failure() {
+    let err = CommandError::HookFailed {
+        phase: "before_deploy",
+        hook: String::from("migrate"),
+        source: Box::new(CommandError::Timeout(Duration::from_secs(60))),
+    };
+    assert_eq!(err.kind(), "timeout");
+    assert_eq!(err.exit_code(), 124);
+}
+
+#[test]
+fn test_container_exit_code_out_of_range() {
+    let exited = |exit_code| CommandError::ContainerExited {
+        container: String::from("app"),
+        exit_code,
+    };
+    assert_eq!(exited(2).exit_code(), 2);
+    assert_eq!(exited(255).exit_code(), 255);
+    assert_eq!(exited(256).exit_code(), 6);
+    assert_eq!(exited(-1).exit_code(), 6);
+}
@@ -28,6 +28,54 @@ impl MainCommand {
             ))
         }
     }
+    fn validate_args_seconds(seconds_str: String) -> Result<(), String> {
+        match seconds_str.parse::<u64>() {
+            Ok(seconds) if seconds > 0 => Ok(()),
+            _ => Err(String::from(
+                "The value should be a positive number of seconds",
+            )),
+        }
+    }
+    fn validate_args_revision(revision_str: String) -> Result<(), String> {
+        match revision_str.parse::<i64>() {
+            Ok(revision) if revision > 0 => Ok(()),
+            _ => Err(String::from("The revision should be a positive number")),
+        }
+    }
+    fn validate_args_selector(selector_str: String) -> Result<(), String> {
+        match selector_str.find('=') {
+            Some(index) if index > 0 => Ok(()),
+            _ => Err(String::from("The selector should be given as `KEY=VALUE`")),
+        }
+    }
+    fn validate_args_count(count_str: String) -> Result<(), String> {
+        match count_str.parse::<usize>() {
+            Ok(count) if count > 0 => Ok(()),
+            _ => Err(String::from("The value should be a positive number")),
+        }
+    }
+    fn validate_args_env(env_str: String) -> Result<(), String> {
+        match env_str.find('=') {
+            Some(index) if index > 0 => Ok(()),
+            _ => Err(String::from("The variable should be given as `KEY=VALUE`")),
+        }
+    }
+    fn validate_args_image(image_str: String) -> Result<(), String> {
+        match image_str.find('=') {
+            Some(index) if index > 0 && index < image_str.len() - 1 => Ok(()),
+            _ => Err(String::from(
+                "The image should be given as `CONTAINER=IMAGE`",
+            )),
+        }
+    }
+    fn validate_args_since(since_str: String) -> Result<(), String> {
+        match service::logs::parse_since(&since_str) {
+            Some(_) => Ok(()),
+            None => Err(String::from(
+                "The value should be a duration like `30s`, `10m`, `2h` or `1d`",
+            )),
+        }
+    }
     fn parse_args_template_variables(args: &ArgMatches) -> Option<BTreeMap<String, String>> {
         args.values_of("CONFIG_TEMPLATE_VARIABLES").map(|vars_str| {
             let mut data = BTreeMap::new();
@@ -40,6 +88,36 @@ impl MainCommand {
         })
     }
 
+    /// Arguments selecting entries besides names, shared by the commands.
+    fn args_selector<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+        vec![
+            Arg::with_name("GROUP")
+                .help("Select the entries of the group in config")
+                .long("group")
+                .value_name("GROUP")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1),
+            Arg::with_name("SELECTOR")
+                .help("Select the entries with the label")
+                .long("selector")
+                .short("l")
+                .value_name("KEY=VALUE")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .validator(MainCommand::validate_args_selector),
+        ]
+    }
+
+    fn parse_args_aws(args: &ArgMatches) -> config::aws::AwsConfig {
+        config::aws::AwsConfig {
+            region: args.value_of("REGION").map(str::to_owned),
+            profile: args.value_of("PROFILE").map(str::to_owned),
+            endpoint_url: args.value_of("ENDPOINT_URL").map(str::to_owned),
+        }
+    }
+
     fn config_file(args: &ArgMatches) -> String {
         if let Some(config_file) = args.value_of("CONFIG") {
             return config_file.to_owned();
@@ -83,6 +161,40 @@ impl MainCommand {
                     .multiple(true)
                     .validator(MainCommand::validate_args_template_variables),
             )
+            .arg(
+                Arg::with_name("DRY_RUN")
+                    .long("dry-run")
+                    .help("Prints requests changing AWS resources instead of sending them"),
+            )
+            .arg(
+                Arg::with_name("OUTPUT")
+                    .long("output")
+                    .value_name("FORMAT")
+                    .possible_values(&["text", "json"])
+                    .default_value("text")
+                    .help("Output format. With json, results are printed to stdout as JSON lines and messages to stderr"),
+            )
+            .arg(
+                Arg::with_name("REGION")
+                    .long("region")
+                    .value_name("REGION")
+                    .help("AWS region, overriding the one in config")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("PROFILE")
+                    .long("profile")
+                    .value_name("PROFILE")
+                    .help("Named profile of AWS credentials, overriding the one in config")
+                    .takes_value(true),
+            )
+            .arg(
+                Arg::with_name("ENDPOINT_URL")
+                    .long("endpoint-url")
+                    .value_name("URL")
+                    .help("Custom endpoint URL of AWS APIs, overriding the one in config")
+                    .takes_value(true),
+            )
             .subcommand(SubCommand::with_name("config").about("Display loaded config"))
             .subcommand(
                 SubCommand::with_name("service")
@@ -92,19 +204,205 @@ impl MainCommand {
                             .about("Deploys ECS service")
                             .arg(
                                 Arg::with_name("NAME")
-                                    .help("Name of the entry in config")
-                                    .required_unless("ALL")
+                                    .help("Names of the entries in config, which may contain `*` and `?`")
+                                    .required_unless_one(&["ALL", "GROUP", "SELECTOR"])
+                                    .multiple(true)
                                     .index(1),
                             )
+                            .args(&MainCommand::args_selector())
                             .arg(
                                 Arg::with_name("ALL")
                                     .help("Deploy all services")
                                     .long("all"),
                             )
+                            .arg(
+                                Arg::with_name("PARALLELISM")
+                                    .help("Maximum number of services deployed at once (default: 4)")
+                                    .long("parallelism")
+                                    .value_name("N")
+                                    .takes_value(true)
+                                    .validator(MainCommand::validate_args_count),
+                            )
                             .arg(
                                 Arg::with_name("NO_WAIT")
                                     .help("Do not wait until new tasks to be running")
                                     .long("no-wait"),
+                            )
+                            .arg(
+                                Arg::with_name("NO_ROLLBACK")
+                                    .help("Do not roll back the service when the deployment fails")
+                                    .long("no-rollback"),
+                            )
+                            .arg(
+                                Arg::with_name("FORCE_NEW_DEPLOYMENT")
+                                    .help("Replace the tasks with the current task definition, without registering")
+                                    .long("force-new-deployment")
+                                    .conflicts_with("IMAGE"),
+                            )
+                            .arg(
+                                Arg::with_name("PROMOTE")
+                                    .help("Reroute the traffic of the CodeDeploy deployment in progress to the new tasks")
+                                    .long("promote")
+                                    .conflicts_with_all(&["ABORT", "IMAGE", "FORCE_NEW_DEPLOYMENT"]),
+                            )
+                            .arg(
+                                Arg::with_name("ABORT")
+                                    .help("Stop the CodeDeploy deployment in progress and roll it back")
+                                    .long("abort")
+                                    .conflicts_with_all(&["IMAGE", "FORCE_NEW_DEPLOYMENT"]),
+                            )
+                            .arg(
+                                Arg::with_name("IMAGE")
+                                    .help("Image of the container to deploy instead of the one in config")
+                                    .long("image")
+                                    .value_name("CONTAINER=IMAGE")
+                                    .takes_value(true)
+                                    .multiple(true)
+                                    .number_of_values(1)
+                                    .validator(MainCommand::validate_args_image),
+                            )
+                            .arg(
+                                Arg::with_name("TIMEOUT")
+                                    .help("Fail if the wait does not finish in the given seconds")
+                                    .long("timeout")
+                                    .value_name("SECONDS")
+                                    .takes_value(true)
+                                    .validator(MainCommand::validate_args_seconds),
+                            )
+                            .arg(
+                                Arg::with_name("POLL_INTERVAL")
+                                    .help("Interval in seconds between checks while waiting")
+                                    .long("poll-interval")
+                                    .value_name("SECONDS")
+                                    .takes_value(true)
+                                    .validator(MainCommand::validate_args_seconds),
+                            ),
+                    )
+                    .subcommand(
+                        SubCommand::with_name("diff")
+                            .about("Shows differences between the running service and config")
+                            .arg(
+                                Arg::with_name("NAME")
+                                    .help("Names of the entries in config, which may contain `*` and `?`")
+                                    .required_unless_one(&["ALL", "GROUP", "SELECTOR"])
+                                    .multiple(true)
+                                    .index(1),
+                            )
+                            .args(&MainCommand::args_selector())
+                            .arg(
+                                Arg::with_name("ALL")
+                                    .help("Show differences of all services")
+                                    .long("all"),
+                            ),
+                    )
+                    .subcommand(
+                        SubCommand::with_name("rollback")
+                            .about("Rolls back ECS service to an earlier revision of the task definition")
+                            .arg(
+                                Arg::with_name("NAME")
+                                    .help("Name of the entry in config")
+                                    .required(true)
+                                    .index(1),
+                            )
+                            .arg(
+                                Arg::with_name("TO")
+                                    .help("Revision to roll back to (default: the previous one)")
+                                    .long("to")
+                                    .value_name("REVISION")
+                                    .takes_value(true)
+                                    .validator(MainCommand::validate_args_revision),
+                            )
+                            .arg(
+                                Arg::with_name("NO_WAIT")
+                                    .help("Do not wait until new tasks to be running")
+                                    .long("no-wait"),
+                            )
+                            .arg(
+                                Arg::with_name("TIMEOUT")
+                                    .help("Fail if the wait does not finish in the given seconds")
+                                    .long("timeout")
+                                    .value_name("SECONDS")
+                                    .takes_value(true)
+                                    .validator(MainCommand::validate_args_seconds),
+                            )
+                            .arg(
+                                Arg::with_name("POLL_INTERVAL")
+                                    .help("Interval in seconds between checks while waiting")
+                                    .long("poll-interval")
+                                    .value_name("SECONDS")
+                                    .takes_value(true)
+                                    .validator(MainCommand::validate_args_seconds),
+                            ),
+                    )
+                    .subcommand(
+                        SubCommand::with_name("logs")
+                            .about("Shows logs of running tasks of ECS service")
+                            .arg(
+                                Arg::with_name("NAME")
+                                    .help("Name of the entry in config")
+                                    .required(true)
+                                    .index(1),
+                            )
+                            .arg(
+                                Arg::with_name("FOLLOW")
+                                    .help("Keep showing new logs")
+                                    .short("f")
+                                    .long("follow"),
+                            )
+                            .arg(
+                                Arg::with_name("SINCE")
+                                    .help("Show logs since the given time ago, like `30s`, `10m` or `2h` (default: 10m)")
+                                    .long("since")
+                                    .value_name("DURATION")
+                                    .takes_value(true)
+                                    .validator(MainCommand::validate_args_since),
+                            )
+                            .arg(
+                                Arg::with_name("FILTER")
+                                    .help("Show only logs matching the CloudWatch Logs filter pattern")
+                                    .long("filter")
+                                    .value_name("PATTERN")
+                                    .takes_value(true),
+                            )
+                            .arg(
+                                Arg::with_name("CONTAINER")
+                                    .help("Show only logs of the container")
+                                    .long("container")
+                                    .value_name("NAME")
+                                    .takes_value(true),
+                            )
+                            .arg(
+                                Arg::with_name("POLL_INTERVAL")
+                                    .help("Interval in seconds between checks while following")
+                                    .long("poll-interval")
+                                    .value_name("SECONDS")
+                                    .takes_value(true)
+                                    .validator(MainCommand::validate_args_seconds),
+                            ),
+                    )
+                    .subcommand(
+                        SubCommand::with_name("status")
+                            .about("Shows deployments, tasks and events of ECS service")
+                            .arg(
+                                Arg::with_name("NAME")
+                                    .help("Names of the entries in config, which may contain `*` and `?`")
+                                    .required_unless_one(&["ALL", "GROUP", "SELECTOR"])
+                                    .multiple(true)
+                                    .index(1),
+                            )
+                            .args(&MainCommand::args_selector())
+                            .arg(
+                                Arg::with_name("ALL")
+                                    .help("Show status of all services")
+                                    .long("all"),
+                            )
+                            .arg(
+                                Arg::with_name("EVENTS")
+                                    .help("Number of the latest events to show (default: 10)")
+                                    .long("events")
+                                    .value_name("COUNT")
+                                    .takes_value(true)
+                                    .validator(MainCommand::validate_args_count),
                             ),
                     )
                     .subcommand(
@@ -112,10 +410,12 @@ impl MainCommand {
                             .about("Stops ECS service (change desired count to zero)")
                             .arg(
                                 Arg::with_name("NAME")
-                                    .help("Name of the entry in config")
-                                    .required_unless("ALL")
+                                    .help("Names of the entries in config, which may contain `*` and `?`")
+                                    .required_unless_one(&["ALL", "GROUP", "SELECTOR"])
+                                    .multiple(true)
                                     .index(1),
                             )
+                            .args(&MainCommand::args_selector())
                             .arg(Arg::with_name("ALL").help("Stop all services").long("all"))
                             .arg(
                                 Arg::with_name("NO_WAIT")
@@ -129,14 +429,87 @@ impl MainCommand {
                     .about("Runs single ESC task")
                     .arg(
                         Arg::with_name("NAME")
-                            .help("Name of the entry in config")
-                            .required(true)
+                            .help("Names of the entries in config, which may contain `*` and `?`")
+                            .required_unless_one(&["GROUP", "SELECTOR"])
+                            .multiple(true)
                             .index(1),
                     )
+                    .args(&MainCommand::args_selector())
+                    .arg(
+                        Arg::with_name("COUNT")
+                            .help("Number of tasks to run (default: 1)")
+                            .long("count")
+                            .value_name("COUNT")
+                            .takes_value(true)
+                            .validator(MainCommand::validate_args_count),
+                    )
                     .arg(
                         Arg::with_name("NO_WAIT")
                             .help("Do not wait until new tasks to be running")
                             .long("no-wait"),
+                    )
+                    .arg(
+                        Arg::with_name("NO_LOGS")
+                            .help("Do not stream logs of the containers while waiting")
+                            .long("no-logs"),
+                    )
+                    .arg(
+                        Arg::with_name("PROPAGATE_EXIT_CODE")
+                            .help("Exit with the exit code of the primary container when it fails")
+                            .long("propagate-exit-code"),
+                    )
+                    .arg(
+                        Arg::with_name("CONTAINER")
+                            .help("Name of the container to override the command and environment variables (default: the first one)")
+                            .long("container")
+                            .value_name("NAME")
+                            .takes_value(true),
+                    )
+                    .arg(
+                        Arg::with_name("ENV")
+                            .help("Environment variable to override")
+                            .long("env")
+                            .value_name("KEY=VALUE")
+                            .takes_value(true)
+                            .multiple(true)
+                            .number_of_values(1)
+                            .validator(MainCommand::validate_args_env),
+                    )
+                    .arg(
+                        Arg::with_name("CPU")
+                            .help("CPU units of the task to override")
+                            .long("cpu")
+                            .value_name("UNITS")
+                            .takes_value(true),
+                    )
+                    .arg(
+                        Arg::with_name("MEMORY")
+                            .help("Memory of the task in MiB to override")
+                            .long("memory")
+                            .value_name("MIB")
+                            .takes_value(true),
+                    )
+                    .arg(
+                        Arg::with_name("COMMAND")
+                            .help("Command to override, given after `--`")
+                            .multiple(true)
+                            .last(true),
+                    )
+                    .arg(
+                        Arg::with_name("TIMEOUT")
+                            .help("Fail if the wait does not finish in the given seconds")
+                            .long("timeout")
+                            .value_name("SECONDS")
+                            .takes_value(true)
+                            .validator(MainCommand::validate_args_seconds),
+                    )
+                    .arg(
+                        Arg::with_name("POLL_INTERVAL")
+                            .help("Interval in seconds between checks while waiting")
+                            .long("poll-interval")
+                            .value_name("SECONDS")
+                            .takes_value(true)
+                            .validator(MainCommand::validate_args_seconds),
                     ),
             )
             .subcommand(
@@ -147,10 +520,12 @@ impl MainCommand {
                             .about("Puts a scheduled task")
                             .arg(
                                 Arg::with_name("NAME")
-                                    .help("Name of the entry in config")
-                                    .required_unless("ALL")
+                                    .help("Names of the entries in config, which may contain `*` and `?`")
+                                    .required_unless_one(&["ALL", "GROUP", "SELECTOR"])
+                                    .multiple(true)
                                     .index(1),
                             )
+                            .args(&MainCommand::args_selector())
                             .arg(
                                 Arg::with_name("ALL")
                                     .help("Put all schedule tasks")
@@ -162,10 +537,12 @@ impl MainCommand {
                             .about("Deletes a scheduled task")
                             .arg(
                                 Arg::with_name("NAME")
-                                    .help("Name of the entry in config")
-                                    .required_unless("ALL")
+                                    .help("Names of the entries in config, which may contain `*` and `?`")
+                                    .required_unless_one(&["ALL", "GROUP", "SELECTOR"])
+                                    .multiple(true)
                                     .index(1),
                             )
+                            .args(&MainCommand::args_selector())
                             .arg(
                                 Arg::with_name("ALL")
                                     .help("Delete all schedule tasks")
@@ -233,6 +610,13 @@ impl MainCommand {
             )
             .get_matches();
 
+        if let Some(format) = matches
+            .value_of("OUTPUT")
+            .and_then(|f| f.parse::<output::Format>().ok())
+        {
+            output::set_format(format);
+        }
+
         let config_file = MainCommand::config_file(&matches);
         info!("config file: {}", config_file);
 
@@ -251,7 +635,10 @@ impl MainCommand {
                 output::PrintLine::error(&format!("Failed loading the configuration: {}", error));
                 return Err(error);
             }
-            Ok(config) => {
+            Ok(mut config) => {
+                config.aws_override = Some(MainCommand::parse_args_aws(&matches));
+                config.dry_run = matches.is_present("DRY_RUN");
+
                 // service
                 if let Some(sub0_matches) = matches.subcommand_matches("service") {
                     if let Some(sub1_matches) = sub0_matches.subcommand_matches("deploy") {
@@ -273,6 +660,82 @@ impl MainCommand {
                         }
                     }
 
+                    if let Some(sub1_matches) = sub0_matches.subcommand_matches("diff") {
+                        info!("start service diff");
+
+                        let cmd = service::diff::Command::from_args(&config, sub1_matches);
+                        match cmd.run().await {
+                            Ok(_) => {
+                                info!("end service diff");
+                                return Ok(());
+                            }
+                            Err(error) => {
+                                output::PrintLine::error(&format!(
+                                    "Failed checking the service: {}",
+                                    error
+                                ));
+                                return Err(error);
+                            }
+                        }
+                    }
+
+                    if let Some(sub1_matches) = sub0_matches.subcommand_matches("rollback") {
+                        info!("start service rollback");
+
+                        let cmd = service::rollback::Command::from_args(&config, sub1_matches);
+                        match cmd.run().await {
+                            Ok(_) => {
+                                info!("end service rollback");
+                                return Ok(());
+                            }
+                            Err(error) => {
+                                output::PrintLine::error(&format!(
+                                    "Failed rolling back the service: {}",
+                                    error
+                                ));
+                                return Err(error);
+                            }
+                        }
+                    }
+
+                    if let Some(sub1_matches) = sub0_matches.subcommand_matches("logs") {
+                        info!("start service logs");
+
+                        let cmd = service::logs::Command::from_args(&config, sub1_matches);
+                        match cmd.run().await {
+                            Ok(_) => {
+                                info!("end service logs");
+                                return Ok(());
+                            }
+                            Err(error) => {
+                                output::PrintLine::error(&format!(
+                                    "Failed getting the logs of the service: {}",
+                                    error
+                                ));
+                                return Err(error);
+                            }
+                        }
+                    }
+
+                    if let Some(sub1_matches) = sub0_matches.subcommand_matches("status") {
+                        info!("start service status");
+
+                        let cmd = service::status::Command::from_args(&config, sub1_matches);
+                        match cmd.run().await {
+                            Ok(_) => {
+                                info!("end service status");
+                                return Ok(());
+                            }
+                            Err(error) => {
+                                output::PrintLine::error(&format!(
+                                    "Failed getting the status of the service: {}",
+                                    error
+                                ));
+                                return Err(error);
+                            }
+                        }
+                    }
+
                     if let Some(sub1_matches) = sub0_matches.subcommand_matches("stop") {
                         info!("start stopping service");
 
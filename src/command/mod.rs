@@ -1,7 +1,13 @@
+mod aws;
 mod cloudwatch_events;
+mod cloudwatch_logs;
+mod codedeploy;
 mod ecs;
 mod error;
 mod main;
+mod mutation;
+mod selector;
+mod wait;
 
 pub mod configtest;
 pub mod params;
@@ -9,4 +15,5 @@ pub mod run_task;
 pub mod schedule_task;
 pub mod service;
 
+pub use self::error::CommandError;
 pub use self::main::MainCommand;
@@ -0,0 +1,25 @@
+use serde::Serialize;
+use serde_json;
+
+use crate::output;
+
+/// Gate of requests which change AWS resources.
+/// In dry-run mode, the requests are printed instead of being sent.
+pub trait Executer {
+    fn dry_run(&self) -> bool;
+
+    /// Returns whether the request should be sent. In dry-run mode, prints it and returns `false`.
+    fn mutable<T: Serialize>(&self, operation: &str, req: &T) -> bool {
+        if !self.dry_run() {
+            return true;
+        }
+
+        output::PrintLine::info(&format!("[dry-run] {}", operation));
+        output::PrintLine::print(&serde_json::to_string_pretty(req).unwrap_or_default());
+        output::PrintResult::emit(&json!({
+            "dry_run": operation,
+            "request": req,
+        }));
+        false
+    }
+}
@@ -2,22 +2,30 @@ use std::default::Default;
 use std::error;
 
 use rusoto_ssm;
-use rusoto_ssm::Ssm;
 
 use super::super::Executer as ParamsExecuter;
 
+use crate::command::aws::ClientFactory;
+use crate::command::mutation::Executer as MutationExecuter;
 use crate::config;
 use crate::output;
 
 pub struct Executer<'c> {
     config: &'c config::command::ParamsConfig,
+    client_factory: &'c ClientFactory,
 }
 
 impl<'c> Executer<'c> {
-    pub fn from_config(config: &'c config::command::ParamsConfig) -> Self {
+    pub fn from_config(
+        config: &'c config::command::ParamsConfig,
+        client_factory: &'c ClientFactory,
+    ) -> Self {
         trace!("command::params::delete::Executer::from_config");
 
-        Executer { config: config }
+        Executer {
+            config,
+            client_factory,
+        }
     }
 
     pub async fn run(&self, name: &str) -> Result<(), Box<dyn error::Error>> {
@@ -28,10 +36,13 @@ impl<'c> Executer<'c> {
             ..Default::default()
         };
 
-        let client = self.client();
-        client.delete_parameter(req).await?;
+        self.delete_parameter(req).await?;
 
         output::PrintLine::success("Finished deleting the parameter");
+        output::PrintResult::emit(&json!({
+            "command": "params delete",
+            "name": name,
+        }));
         Ok(())
     }
 }
@@ -40,4 +51,14 @@ impl<'c> ParamsExecuter for Executer<'c> {
     fn config(&self) -> &config::command::ParamsConfig {
         &self.config
     }
+
+    fn client_factory(&self) -> &ClientFactory {
+        self.client_factory
+    }
+}
+
+impl<'c> MutationExecuter for Executer<'c> {
+    fn dry_run(&self) -> bool {
+        self.client_factory.dry_run()
+    }
 }
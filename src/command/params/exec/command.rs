@@ -2,6 +2,7 @@ use std::error;
 
 use clap;
 
+use crate::command::aws::ClientFactory;
 use crate::config;
 
 use super::executer::Executer;
@@ -50,7 +51,9 @@ impl<'c> Command<'c> {
     pub async fn run(&self) -> Result<(), Box<dyn error::Error>> {
         trace!("command::params::exec::Command::run");
         if let Some(params_config) = self.config.params.as_ref() {
-            let exec = Executer::from_config(params_config);
+            let aws_config = self.config.aws_config(params_config.aws.as_ref());
+            let client_factory = ClientFactory::from_config(&aws_config, self.config.dry_run)?;
+            let exec = Executer::from_config(params_config, &client_factory);
             exec.run(&self.program, &self.args).await?;
         }
         Ok(())
@@ -1,22 +1,30 @@
 use std::error;
 use std::process;
 
+use crate::command::aws::ClientFactory;
 use crate::command::error::CommandError;
+use crate::command::mutation::Executer as MutationExecuter;
 use crate::config;
-use crate::output;
 
 use super::super::Executer as ParamsExecuter;
 use super::{Arguments, Program};
 
 pub struct Executer<'c> {
     config: &'c config::command::ParamsConfig,
+    client_factory: &'c ClientFactory,
 }
 
 impl<'c> Executer<'c> {
-    pub fn from_config(config: &'c config::command::ParamsConfig) -> Self {
+    pub fn from_config(
+        config: &'c config::command::ParamsConfig,
+        client_factory: &'c ClientFactory,
+    ) -> Self {
         trace!("command::params::exec::Executer::from_config");
 
-        Executer { config: config }
+        Executer {
+            config,
+            client_factory,
+        }
     }
 
     pub async fn run(
@@ -45,14 +53,9 @@ impl<'c> Executer<'c> {
         let mut child = cmd.spawn()?;
         let output = child.wait()?;
 
-        if output.success() {
-            Ok(())
-        } else {
-            output::PrintLine::error(&format!(
-                "Command exit with status code: {}",
-                output.code().unwrap_or(0)
-            ));
-            Err(Box::new(CommandError::Unknown))
+        match output.code() {
+            Some(0) => Ok(()),
+            code => Err(Box::new(CommandError::ProcessExited(code))),
         }
     }
 }
@@ -61,4 +64,14 @@ impl<'c> ParamsExecuter for Executer<'c> {
     fn config(&self) -> &config::command::ParamsConfig {
         &self.config
     }
+
+    fn client_factory(&self) -> &ClientFactory {
+        self.client_factory
+    }
+}
+
+impl<'c> MutationExecuter for Executer<'c> {
+    fn dry_run(&self) -> bool {
+        self.client_factory.dry_run()
+    }
 }
@@ -1,18 +1,21 @@
 use async_trait::async_trait;
-use rusoto_core::Region;
 use rusoto_ssm;
 use rusoto_ssm::{Ssm, SsmClient};
 use std::error;
 
+use crate::command::aws::ClientFactory;
 use crate::command::error::CommandError;
+use crate::command::mutation::Executer as MutationExecuter;
 use crate::config;
 
 #[async_trait]
-pub trait Executer {
+pub trait Executer: MutationExecuter {
     fn client(&self) -> SsmClient {
-        return SsmClient::new(Region::ApNortheast1);
+        self.client_factory().ssm()
     }
 
+    fn client_factory(&self) -> &ClientFactory;
+
     fn config(&self) -> &config::command::ParamsConfig;
 
     fn name_with_path(&self, name: &str) -> String {
@@ -42,7 +45,9 @@ pub trait Executer {
         if name.starts_with(&path) {
             return Ok(name.trim_left_matches(&path));
         } else {
-            Err(Box::new(CommandError::Unknown))
+            Err(Box::new(CommandError::InvalidParameterName(
+                name.to_owned(),
+            )))
         }
     }
 
@@ -58,7 +63,10 @@ pub trait Executer {
         };
 
         let client = self.client();
-        let mut res = client.get_parameters_by_path(req).await?;
+        let mut res = client
+            .get_parameters_by_path(req)
+            .await
+            .map_err(|e| CommandError::aws_api("SSM", "GetParametersByPath", e))?;
 
         let mut params: Vec<rusoto_ssm::Parameter> = Vec::new();
         if let Some(new_params) = res.parameters {
@@ -73,7 +81,10 @@ pub trait Executer {
                 next_token: Some(next_token),
                 ..Default::default()
             };
-            res = client.get_parameters_by_path(req).await?;
+            res = client
+                .get_parameters_by_path(req)
+                .await
+                .map_err(|e| CommandError::aws_api("SSM", "GetParametersByPath", e))?;
 
             if let Some(new_params) = res.parameters {
                 params.extend(new_params.into_iter());
@@ -83,4 +94,48 @@ pub trait Executer {
         info!("get parameters-by-path successfully");
         Ok(params)
     }
+
+    async fn put_parameter(
+        &self,
+        req: rusoto_ssm::PutParameterRequest,
+    ) -> Result<(), Box<dyn error::Error>> {
+        trace!("command::params::Executer::put_parameter");
+
+        // Values of secure parameters are not shown
+        let printable_req = match req.type_.as_deref() {
+            Some("SecureString") => rusoto_ssm::PutParameterRequest {
+                value: String::from("********"),
+                ..req.to_owned()
+            },
+            _ => req.to_owned(),
+        };
+        if !self.mutable("SSM PutParameter", &printable_req) {
+            return Ok(());
+        }
+
+        self.client()
+            .put_parameter(req)
+            .await
+            .map_err(|e| CommandError::aws_api("SSM", "PutParameter", e))?;
+        info!("put parameter successfully");
+        Ok(())
+    }
+
+    async fn delete_parameter(
+        &self,
+        req: rusoto_ssm::DeleteParameterRequest,
+    ) -> Result<(), Box<dyn error::Error>> {
+        trace!("command::params::Executer::delete_parameter");
+
+        if !self.mutable("SSM DeleteParameter", &req) {
+            return Ok(());
+        }
+
+        self.client()
+            .delete_parameter(req)
+            .await
+            .map_err(|e| CommandError::aws_api("SSM", "DeleteParameter", e))?;
+        info!("delete parameter successfully");
+        Ok(())
+    }
 }
@@ -5,18 +5,28 @@ use rusoto_ssm;
 use rusoto_ssm::Ssm;
 
 use super::super::Executer as ParamsExecuter;
+use crate::command::aws::ClientFactory;
+use crate::command::error::CommandError;
+use crate::command::mutation::Executer as MutationExecuter;
 use crate::config;
 use crate::output;
 
 pub struct Executer<'c> {
     config: &'c config::command::ParamsConfig,
+    client_factory: &'c ClientFactory,
 }
 
 impl<'c> Executer<'c> {
-    pub fn from_config(config: &'c config::command::ParamsConfig) -> Self {
+    pub fn from_config(
+        config: &'c config::command::ParamsConfig,
+        client_factory: &'c ClientFactory,
+    ) -> Self {
         trace!("command::params::get::Executer::from_config");
 
-        Executer { config: config }
+        Executer {
+            config,
+            client_factory,
+        }
     }
 
     pub async fn run(&self, name: &str) -> Result<(), Box<dyn error::Error>> {
@@ -32,18 +42,27 @@ impl<'c> Executer<'c> {
         };
 
         let client = self.client();
-        let res = client.get_parameter(req).await?;
+        let res = client
+            .get_parameter(req)
+            .await
+            .map_err(|e| CommandError::aws_api("SSM", "GetParameter", e))?;
 
         if let Some(params) = res.parameter {
-            self.print(&params);
+            self.print(name, &params);
         }
 
         Ok(())
     }
 
-    fn print(&self, param: &rusoto_ssm::Parameter) {
+    fn print(&self, name: &str, param: &rusoto_ssm::Parameter) {
         if let Some(val) = param.value.as_ref() {
-            output::PrintLine::print(val);
+            match output::format() {
+                output::Format::Text => output::PrintLine::print(val),
+                output::Format::Json => output::PrintResult::emit(&json!({
+                    "name": name,
+                    "value": val,
+                })),
+            }
         }
     }
 }
@@ -52,4 +71,14 @@ impl<'c> ParamsExecuter for Executer<'c> {
     fn config(&self) -> &config::command::ParamsConfig {
         &self.config
     }
+
+    fn client_factory(&self) -> &ClientFactory {
+        self.client_factory
+    }
+}
+
+impl<'c> MutationExecuter for Executer<'c> {
+    fn dry_run(&self) -> bool {
+        self.client_factory.dry_run()
+    }
 }
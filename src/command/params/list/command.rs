@@ -3,6 +3,7 @@ use std::error;
 use clap;
 
 use super::executer::Executer;
+use crate::command::aws::ClientFactory;
 use crate::config;
 
 pub struct Command<'c> {
@@ -25,7 +26,9 @@ impl<'c> Command<'c> {
     pub async fn run(&self) -> Result<(), Box<dyn error::Error>> {
         trace!("command::params::list::Command::run");
         if let Some(params_config) = self.config.params.as_ref() {
-            let exec = Executer::from_config(params_config);
+            let aws_config = self.config.aws_config(params_config.aws.as_ref());
+            let client_factory = ClientFactory::from_config(&aws_config, self.config.dry_run)?;
+            let exec = Executer::from_config(params_config, &client_factory);
             exec.run().await?;
         }
         Ok(())
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::error;
 use std::io::stdout;
 use std::io::Write;
@@ -6,17 +7,27 @@ use tabwriter::TabWriter;
 use rusoto_ssm;
 
 use super::super::Executer as ParamsExecuter;
+use crate::command::aws::ClientFactory;
+use crate::command::mutation::Executer as MutationExecuter;
 use crate::config;
+use crate::output;
 
 pub struct Executer<'c> {
     config: &'c config::command::ParamsConfig,
+    client_factory: &'c ClientFactory,
 }
 
 impl<'c> Executer<'c> {
-    pub fn from_config(config: &'c config::command::ParamsConfig) -> Self {
+    pub fn from_config(
+        config: &'c config::command::ParamsConfig,
+        client_factory: &'c ClientFactory,
+    ) -> Self {
         trace!("command::params::list::Executer::from_config");
 
-        Executer { config: config }
+        Executer {
+            config,
+            client_factory,
+        }
     }
 
     pub async fn run(&self) -> Result<(), Box<dyn error::Error>> {
@@ -29,6 +40,17 @@ impl<'c> Executer<'c> {
     }
 
     fn print(&self, params: &Vec<rusoto_ssm::Parameter>) -> Result<(), Box<dyn error::Error>> {
+        if output::format() == output::Format::Json {
+            let mut values = BTreeMap::new();
+            for p in params.iter() {
+                if let (Some(name_with_path), Some(value)) = (p.name.as_ref(), p.value.as_ref()) {
+                    values.insert(self.strip_path(name_with_path)?, value);
+                }
+            }
+            output::PrintResult::emit(&values);
+            return Ok(());
+        }
+
         let mut tw = TabWriter::new(stdout());
 
         for p in params.iter() {
@@ -47,4 +69,14 @@ impl<'c> ParamsExecuter for Executer<'c> {
     fn config(&self) -> &config::command::ParamsConfig {
         &self.config
     }
+
+    fn client_factory(&self) -> &ClientFactory {
+        self.client_factory
+    }
+}
+
+impl<'c> MutationExecuter for Executer<'c> {
+    fn dry_run(&self) -> bool {
+        self.client_factory.dry_run()
+    }
 }
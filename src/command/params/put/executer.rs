@@ -2,21 +2,29 @@ use std::default::Default;
 use std::error;
 
 use rusoto_ssm;
-use rusoto_ssm::Ssm;
 
 use super::super::Executer as ParamsExecuter;
+use crate::command::aws::ClientFactory;
+use crate::command::mutation::Executer as MutationExecuter;
 use crate::config;
 use crate::output;
 
 pub struct Executer<'c> {
     config: &'c config::command::ParamsConfig,
+    client_factory: &'c ClientFactory,
 }
 
 impl<'c> Executer<'c> {
-    pub fn from_config(config: &'c config::command::ParamsConfig) -> Self {
+    pub fn from_config(
+        config: &'c config::command::ParamsConfig,
+        client_factory: &'c ClientFactory,
+    ) -> Self {
         trace!("command::params::put::Executer::from_config");
 
-        Executer { config: config }
+        Executer {
+            config,
+            client_factory,
+        }
     }
 
     pub async fn run(&self, name: &str, value: &str) -> Result<(), Box<dyn error::Error>> {
@@ -37,10 +45,13 @@ impl<'c> Executer<'c> {
             ..Default::default()
         };
 
-        let client = self.client();
-        client.put_parameter(req).await?;
+        self.put_parameter(req).await?;
 
         output::PrintLine::success("Finished put the parameter");
+        output::PrintResult::emit(&json!({
+            "command": "params put",
+            "name": name,
+        }));
         Ok(())
     }
 }
@@ -49,4 +60,14 @@ impl<'c> ParamsExecuter for Executer<'c> {
     fn config(&self) -> &config::command::ParamsConfig {
         &self.config
     }
+
+    fn client_factory(&self) -> &ClientFactory {
+        self.client_factory
+    }
+}
+
+impl<'c> MutationExecuter for Executer<'c> {
+    fn dry_run(&self) -> bool {
+        self.client_factory.dry_run()
+    }
 }
@@ -2,14 +2,28 @@ use std::error;
 
 use clap;
 
+use crate::command::aws::ClientFactory;
+use crate::command::error::CommandError;
+use crate::command::selector::Selector;
+use crate::command::wait;
 use crate::config;
 
 use super::executer::{Executer, ExecuterOptions};
 
 pub struct Command<'c> {
     config: &'c config::command::Config,
-    name: &'c str,
+    selector: Selector<'c>,
+    count: Option<i64>,
     no_wait: bool,
+    no_logs: bool,
+    propagate_exit_code: bool,
+    timeout: Option<u64>,
+    poll_interval: Option<u64>,
+    container: Option<&'c str>,
+    command: Option<Vec<&'c str>>,
+    environment: Vec<(&'c str, &'c str)>,
+    cpu: Option<&'c str>,
+    memory: Option<&'c str>,
 }
 
 impl<'c> Command<'c> {
@@ -18,8 +32,28 @@ impl<'c> Command<'c> {
 
         Command {
             config: config,
-            name: args.value_of("NAME").unwrap(),
+            selector: Selector::from_args(args),
+            count: args.value_of("COUNT").and_then(|v| v.parse().ok()),
             no_wait: args.is_present("NO_WAIT"),
+            no_logs: args.is_present("NO_LOGS"),
+            propagate_exit_code: args.is_present("PROPAGATE_EXIT_CODE"),
+            timeout: args.value_of("TIMEOUT").and_then(|v| v.parse().ok()),
+            poll_interval: args.value_of("POLL_INTERVAL").and_then(|v| v.parse().ok()),
+            container: args.value_of("CONTAINER"),
+            command: args.values_of("COMMAND").map(|values| values.collect()),
+            environment: args
+                .values_of("ENV")
+                .map(|values| {
+                    values
+                        .filter_map(|value| {
+                            let mut pair = value.splitn(2, '=');
+                            Some((pair.next()?, pair.next()?))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+            cpu: args.value_of("CPU"),
+            memory: args.value_of("MEMORY"),
         }
     }
 
@@ -28,28 +62,117 @@ impl<'c> Command<'c> {
 
         Command {
             config: config,
-            name: name,
+            selector: Selector::new(Some(name), false),
+            count: None,
             no_wait: no_wait,
+            no_logs: false,
+            propagate_exit_code: false,
+            timeout: None,
+            poll_interval: None,
+            container: None,
+            command: None,
+            environment: vec![],
+            cpu: None,
+            memory: None,
         }
     }
 
     pub async fn run(&self) -> Result<(), Box<dyn error::Error>> {
         trace!("command::run_task::Command::run");
 
-        if let Some(run_task_config_group) = self.config.run_task.as_ref() {
-            for run_task_config in run_task_config_group {
-                if run_task_config.name != self.name {
-                    continue;
-                }
-
-                let options = ExecuterOptions {
-                    no_wait: self.no_wait,
-                };
-                let ecs_run_task_cmd = Executer::from_config(&run_task_config, &options);
-                ecs_run_task_cmd.run().await?;
-            }
+        let run_task_configs =
+            self.selector
+                .select(self.config, "run_task", self.config.run_task.as_ref())?;
+        for run_task_config in run_task_configs {
+            let (timeout, poll_interval) = wait::durations(
+                self.timeout,
+                self.poll_interval,
+                run_task_config.timeout,
+                run_task_config.poll_interval,
+            );
+            let options = ExecuterOptions {
+                overrides: self.overrides(run_task_config)?,
+                count: self.count,
+                no_wait: self.no_wait,
+                no_logs: self.no_logs,
+                propagate_exit_code: self.propagate_exit_code,
+                timeout,
+                poll_interval,
+            };
+            let aws_config = self.config.aws_config(run_task_config.aws.as_ref());
+            let client_factory = ClientFactory::from_config(&aws_config, self.config.dry_run)?;
+            let ecs_run_task_cmd =
+                Executer::from_config(run_task_config, &client_factory, &options);
+            ecs_run_task_cmd.run().await?;
         }
 
         Ok(())
     }
+
+    /// Builds overrides from the command line arguments, on top of ones in the entry.
+    fn overrides(
+        &self,
+        run_task_config: &config::command::RunTaskConfig,
+    ) -> Result<Option<config::ecs::TaskOverride>, Box<dyn error::Error>> {
+        let mut args_overrides = config::ecs::TaskOverride {
+            cpu: self.cpu.map(|v| v.to_owned()),
+            memory: self.memory.map(|v| v.to_owned()),
+            ..Default::default()
+        };
+
+        if self.command.is_some() || !self.environment.is_empty() {
+            let container_definitions = &run_task_config.task_definition.container_definitions;
+            // The first container is overridden by default.
+            let container_name = match self.container {
+                Some(name) => container_definitions
+                    .iter()
+                    .find(|cd| cd.name.as_deref() == Some(name))
+                    .and_then(|cd| cd.name.to_owned())
+                    .ok_or(CommandError::ConfigEntryNotFound {
+                        section: "container_definitions",
+                        name: name.to_owned(),
+                    })?,
+                None => container_definitions
+                    .first()
+                    .and_then(|cd| cd.name.to_owned())
+                    .unwrap_or_default(),
+            };
+
+            args_overrides.container_overrides = Some(vec![config::ecs::ContainerOverride {
+                name: container_name,
+                command: self
+                    .command
+                    .as_ref()
+                    .map(|command| command.iter().map(|c| (*c).to_owned()).collect()),
+                environment: if self.environment.is_empty() {
+                    None
+                } else {
+                    Some(
+                        self.environment
+                            .iter()
+                            .map(|(name, value)| config::ecs::KeyValuePair {
+                                name: Some((*name).to_owned()),
+                                value: Some((*value).to_owned()),
+                            })
+                            .collect(),
+                    )
+                },
+                ..Default::default()
+            }]);
+        }
+
+        let overrides = match run_task_config.overrides.as_ref() {
+            Some(conf_overrides) => conf_overrides.merge(&args_overrides),
+            None => args_overrides,
+        };
+        if overrides.container_overrides.is_none()
+            && overrides.cpu.is_none()
+            && overrides.memory.is_none()
+            && overrides.task_role_arn.is_none()
+            && overrides.execution_role_arn.is_none()
+        {
+            return Ok(None);
+        }
+        Ok(Some(overrides))
+    }
 }
@@ -1,22 +1,97 @@
 use std::error;
-use std::thread::sleep;
-use std::time::Duration;
+use std::io::Write;
+use std::time::{Duration, Instant};
+use tabwriter::TabWriter;
 
-use rusoto_core::Region;
+use tokio::time::sleep;
+
+use rusoto_ecs;
 use rusoto_ecs::EcsClient;
+use rusoto_logs::CloudWatchLogsClient;
+use serde_json;
 
 use super::super::error::CommandError;
+use crate::command::aws::{ClientFactory, JsonClient};
+use crate::command::cloudwatch_logs::Executer as CloudWatchLogsExecuter;
+use crate::command::cloudwatch_logs::{LogStream, LogTail};
 use crate::command::ecs::Executer as EcsExecuter;
+use crate::command::ecs::JsonExecuter as EcsJsonExecuter;
 use crate::command::ecs::TaskDescription;
+use crate::command::mutation::Executer as MutationExecuter;
+use crate::command::wait;
 use crate::config;
 use crate::output;
 
 pub struct ExecuterOptions {
+    pub overrides: Option<config::ecs::TaskOverride>,
+    pub count: Option<i64>,
     pub no_wait: bool,
+    pub no_logs: bool,
+    pub propagate_exit_code: bool,
+    pub timeout: Option<Duration>,
+    pub poll_interval: Duration,
+}
+
+/// Exit status of a container in a stopped task.
+struct ContainerOutcome {
+    name: String,
+    essential: bool,
+    exit_code: Option<i64>,
+    reason: Option<String>,
+}
+
+/// State of a task started by the command.
+/// The exit code is the one of the primary container, which is the first essential one.
+struct TaskOutcome {
+    task_arn: Option<String>,
+    stopped: bool,
+    exit_code: Option<i64>,
+    containers: Vec<ContainerOutcome>,
+    error: Option<Box<dyn error::Error>>,
+}
+
+impl TaskOutcome {
+    fn from_description(description: &TaskDescription) -> Self {
+        let error = description.failure.as_ref().map(|failure| {
+            Box::new(CommandError::TaskFailed {
+                exit_code: None,
+                reason: failure.reason.to_owned().unwrap_or_default(),
+            }) as Box<dyn error::Error>
+        });
+
+        TaskOutcome {
+            task_arn: description
+                .task
+                .as_ref()
+                .and_then(|t| t.task_arn.to_owned()),
+            stopped: error.is_some(),
+            exit_code: None,
+            containers: vec![],
+            error,
+        }
+    }
+
+    fn task_id(&self) -> &str {
+        self.task_arn
+            .as_ref()
+            .and_then(|arn| arn.rsplit('/').next())
+            .unwrap_or("-")
+    }
+
+    fn status(&self) -> &'static str {
+        match (self.stopped, self.error.is_some()) {
+            (_, true) => "FAILED",
+            (true, false) => "SUCCEEDED",
+            (false, false) => "STARTED",
+        }
+    }
 }
 
 pub struct Executer<'c> {
     ecs_client: EcsClient,
+    ecs_json_client: JsonClient,
+    logs_client: CloudWatchLogsClient,
+    dry_run: bool,
     config: &'c config::command::RunTaskConfig,
     options: &'c ExecuterOptions,
 }
@@ -24,13 +99,16 @@ pub struct Executer<'c> {
 impl<'c> Executer<'c> {
     pub fn from_config(
         config: &'c config::command::RunTaskConfig,
+        client_factory: &ClientFactory,
         options: &'c ExecuterOptions,
     ) -> Self {
         trace!("command::run_task::Executer::from_config");
 
-        let client = EcsClient::new(Region::ApNortheast1);
         Executer {
-            ecs_client: client,
+            ecs_client: client_factory.ecs(),
+            ecs_json_client: client_factory.ecs_json(),
+            logs_client: client_factory.logs(),
+            dry_run: client_factory.dry_run(),
             config: config,
             options: options,
         }
@@ -39,132 +117,341 @@ impl<'c> Executer<'c> {
     pub async fn run(&self) -> Result<(), Box<dyn error::Error>> {
         trace!("command::run_task::Executer::run");
 
+        let started_at = Instant::now();
         output::PrintLine::info("Registering a task definition");
         let task_definition = self
             .register_task_definition(&self.config.task_definition)
             .await?;
-        let task_definition_arn = task_definition
-            .task_definition_arn
-            .as_ref()
-            .ok_or(Box::new(CommandError::Unknown))?;
+        let task_definition_arn = task_definition.task_definition_arn.as_ref().ok_or(
+            CommandError::TaskDefinitionNotFound(self.config.task_definition.family.to_owned()),
+        )?;
 
-        output::PrintLine::info("Starting to run the task");
-        let running_task = self
+        let count = self.options.count.or(self.config.count).unwrap_or(1);
+        if count == 1 {
+            output::PrintLine::info("Starting to run the task");
+        } else {
+            output::PrintLine::info(&format!("Starting to run {} tasks", count));
+        }
+        let descriptions = self
             .run_task(
-                &self.config.cluster,
+                self.config,
                 &task_definition_arn,
-                self.config.launch_type.as_ref().map(|s| s.as_str()),
-                self.config.network_configuration.as_ref(),
-                self.config.platform_version.as_ref().map(|s| s.as_str()),
-                self.config.enable_execute_command,
+                self.options.overrides.as_ref(),
+                count,
             )
             .await?;
+        let mut outcomes: Vec<TaskOutcome> = descriptions
+            .iter()
+            .map(TaskOutcome::from_description)
+            .collect();
+
+        let waited = !self.options.no_wait && !self.dry_run;
+        if waited {
+            self.wait_for_stopped(&mut outcomes, &task_definition)
+                .await?;
+        }
+
+        let failed = outcomes.iter().filter(|o| o.error.is_some()).count();
+        if waited || failed > 0 {
+            output::PrintLine::info("");
+            self.print(&outcomes)?;
+        }
 
-        if !self.options.no_wait {
-            self.wait_for_stopped(&running_task).await?;
+        output::PrintResult::emit(&json!({
+            "command": "run-task",
+            "name": self.config.name,
+            "cluster": self.config.cluster,
+            "task_definition_arn": task_definition_arn,
+            "revision": task_definition.revision,
+            "tasks": outcomes.iter().map(|outcome| json!({
+                "task_arn": outcome.task_arn,
+                "status": outcome.status(),
+                "exit_code": outcome.exit_code,
+                "containers": outcome.containers.iter().map(|container| json!({
+                    "name": container.name,
+                    "essential": container.essential,
+                    "exit_code": container.exit_code,
+                    "reason": container.reason,
+                })).collect::<Vec<serde_json::Value>>(),
+                "error": outcome.error.as_ref().map(|e| e.to_string()),
+            })).collect::<Vec<serde_json::Value>>(),
+            "elapsed_seconds": started_at.elapsed().as_secs_f64(),
+        }));
+
+        if failed > 0 {
+            if self.options.propagate_exit_code {
+                let primary = outcomes
+                    .iter()
+                    .filter(|o| o.error.is_some())
+                    .find_map(|o| o.containers.iter().find(|c| c.essential));
+                if let Some(ContainerOutcome {
+                    name,
+                    exit_code: Some(code),
+                    ..
+                }) = primary
+                {
+                    if *code != 0 {
+                        return Err(Box::new(CommandError::ContainerExited {
+                            container: name.to_owned(),
+                            exit_code: *code,
+                        }));
+                    }
+                }
+            }
+
+            let total = outcomes.len();
+            if total == 1 {
+                if let Some(error) = outcomes.into_iter().find_map(|o| o.error) {
+                    return Err(error);
+                }
+            }
+            return Err(Box::new(CommandError::TaskFailed {
+                exit_code: None,
+                reason: format!("{} of {} tasks failed", failed, total),
+            }));
         }
 
         output::PrintLine::success("Finished running the task");
         Ok(())
     }
 
+    fn print(&self, outcomes: &[TaskOutcome]) -> Result<(), Box<dyn error::Error>> {
+        let mut tw = TabWriter::new(output::text_writer());
+
+        writeln!(
+            &mut tw,
+            "TASK\tSTATUS\tCONTAINER\tESSENTIAL\tEXIT CODE\tREASON"
+        )?;
+        for outcome in outcomes.iter() {
+            if outcome.containers.is_empty() {
+                writeln!(
+                    &mut tw,
+                    "{}\t{}\t\t\t\t{}",
+                    outcome.task_id(),
+                    outcome.status(),
+                    outcome
+                        .error
+                        .as_ref()
+                        .map(|e| e.to_string())
+                        .unwrap_or_default(),
+                )?;
+                continue;
+            }
+
+            for container in outcome.containers.iter() {
+                writeln!(
+                    &mut tw,
+                    "{}\t{}\t{}\t{}\t{}\t{}",
+                    outcome.task_id(),
+                    outcome.status(),
+                    container.name,
+                    if container.essential { "yes" } else { "no" },
+                    container
+                        .exit_code
+                        .map(|code| code.to_string())
+                        .unwrap_or_default(),
+                    container.reason.as_deref().unwrap_or(""),
+                )?;
+            }
+        }
+
+        tw.flush()?;
+        Ok(())
+    }
+
     async fn wait_for_stopped(
         &self,
-        running_task: &TaskDescription,
+        outcomes: &mut [TaskOutcome],
+        task_definition: &rusoto_ecs::TaskDefinition,
     ) -> Result<(), Box<dyn error::Error>> {
         trace!("command::run-task::Executer::wait_for_stopped");
 
-        fn check_stopped(current_task: &TaskDescription) -> Result<bool, Box<dyn error::Error>> {
-            if let Some(failure) = current_task.failure.as_ref() {
-                let reason = failure.reason.as_ref().map(String::as_str).unwrap_or("");
-                output::PrintLine::error(&format!("Finished task with error :{}", reason));
-                return Err(Box::new(CommandError::Unknown));
-            }
+        let mut tails: Vec<LogTail> = if self.options.no_logs {
+            vec![]
+        } else {
+            // The task IDs are needed to tell the logs apart only when running multiple tasks.
+            let multiple = outcomes.len() > 1;
+            outcomes
+                .iter()
+                .filter_map(|outcome| outcome.task_arn.as_ref())
+                .flat_map(|task_arn| LogStream::from_task(task_definition, task_arn))
+                .map(|stream| LogTail::new(stream, multiple))
+                .collect()
+        };
+
+        wait::with_timeout(
+            self.options.timeout,
+            self.poll_for_stopped(outcomes, &mut tails),
+        )
+        .await
+    }
+
+    async fn poll_for_stopped(
+        &self,
+        outcomes: &mut [TaskOutcome],
+        tails: &mut [LogTail],
+    ) -> Result<(), Box<dyn error::Error>> {
+        trace!("command::run-task::Executer::poll_for_stopped");
 
-            match current_task.task.as_ref() {
-                None => {
-                    output::PrintLine::error("No task found");
-                    return Err(Box::new(CommandError::Unknown));
+        loop {
+            let pending_arns: Vec<String> = outcomes
+                .iter()
+                .filter(|outcome| !outcome.stopped)
+                .filter_map(|outcome| outcome.task_arn.to_owned())
+                .collect();
+            if pending_arns.is_empty() {
+                // The last events may be delivered after the tasks stop.
+                for tail in tails.iter_mut() {
+                    self.tail_log_events(tail).await?;
                 }
-                Some(task) => {
-                    let status = task
-                        .last_status
-                        .as_ref()
-                        .ok_or(Box::new(CommandError::Unknown))?;
-                    if status == "STOPPED" {
-                        if let Some(reason) = task.stopped_reason.as_ref() {
-                            if reason != "Essential container in task exited" {
-                                output::PrintLine::error(&format!(
-                                    "The task stopped with reason: {}",
-                                    reason
-                                ));
-                                return Err(Box::new(CommandError::Unknown));
-                            }
-                        }
-
-                        let essential_container = task
-                            .containers
-                            .as_ref()
-                            .and_then(|c| c.first())
-                            .ok_or(Box::new(CommandError::Unknown))?;
-
-                        match essential_container.exit_code {
-                            Some(0) => return Ok(true), // stopped task successfully!
-                            Some(code) => {
-                                output::PrintLine::error(&format!(
-                                    "The container in the task exited with code: {}",
-                                    code
-                                ));
-                                return Err(Box::new(CommandError::Unknown));
-                            }
-                            None => {
-                                let reason = essential_container
-                                    .reason
-                                    .as_ref()
-                                    .map(String::as_str)
-                                    .unwrap_or("");
-                                output::PrintLine::error(&format!(
-                                    "Failed running task by some reason: {}",
-                                    reason
-                                ));
-                                return Err(Box::new(CommandError::Unknown));
-                            }
-                        }
-                    }
+                return Ok(());
+            }
+
+            if tails.is_empty() {
+                if outcomes.len() == 1 {
+                    output::PrintLine::info("Waiting for the task to be stopped...");
+                } else {
+                    output::PrintLine::info(&format!(
+                        "Waiting for {} tasks to be stopped...",
+                        pending_arns.len()
+                    ));
                 }
-            };
+            }
+            sleep(self.options.poll_interval).await;
 
-            // running task yet
-            Ok(false)
-        }
+            for tail in tails.iter_mut() {
+                self.tail_log_events(tail).await?;
+            }
+
+            let multiple = outcomes.len() > 1;
+            let container_definitions = &self.config.task_definition.container_definitions;
+            let tasks = self
+                .describe_tasks(&self.config.cluster, &pending_arns)
+                .await?;
+            for task in tasks.iter() {
+                let outcome = match outcomes
+                    .iter_mut()
+                    .find(|outcome| outcome.task_arn == task.task_arn)
+                {
+                    Some(outcome) => outcome,
+                    None => continue,
+                };
 
-        let stopped = check_stopped(running_task)?;
-        if stopped {
-            return Ok(());
+                let containers = match check_stopped(task, container_definitions)? {
+                    Some(containers) => containers,
+                    None => continue,
+                };
+                outcome.stopped = true;
+                outcome.exit_code = containers
+                    .iter()
+                    .find(|container| container.essential)
+                    .and_then(|container| container.exit_code);
+                outcome.error =
+                    task_failure(task, &containers).map(|e| Box::new(e) as Box<dyn error::Error>);
+                outcome.containers = containers;
+
+                if multiple {
+                    output::PrintLine::info(&format!(
+                        "Task {} stopped: {}",
+                        outcome.task_id(),
+                        outcome.status()
+                    ));
+                }
+            }
         }
+    }
+}
 
-        let task_arn = &running_task
-            .task
-            .as_ref()
-            .unwrap()
-            .task_arn
-            .as_ref()
-            .unwrap();
+/// Returns the exit status of the containers, once the task stops.
+fn check_stopped(
+    task: &rusoto_ecs::Task,
+    container_definitions: &config::ecs::ContainerDefinitions,
+) -> Result<Option<Vec<ContainerOutcome>>, Box<dyn error::Error>> {
+    let status = task
+        .last_status
+        .as_ref()
+        .ok_or(CommandError::UnexpectedResponse {
+            operation: "DescribeTasks",
+            detail: "no last status of the task",
+        })?;
+    if status != "STOPPED" {
+        // running task yet
+        return Ok(None);
+    }
 
-        // TODO: Timeout
-        loop {
-            output::PrintLine::info("Waiting for the task to be stopped...");
-            sleep(Duration::from_millis(2000));
+    let containers = task
+        .containers
+        .as_ref()
+        .ok_or(CommandError::UnexpectedResponse {
+            operation: "DescribeTasks",
+            detail: "no containers of the task",
+        })?;
 
-            let current_task = self.describe_task(&self.config.cluster, task_arn).await?;
-            let stopped = check_stopped(&current_task)?;
-            if stopped {
-                return Ok(());
+    // Containers are essential unless it is turned off in the definition.
+    let mut outcomes: Vec<ContainerOutcome> = containers
+        .iter()
+        .map(|container| {
+            let name = container.name.to_owned().unwrap_or_default();
+            let essential = container_definitions
+                .iter()
+                .find(|cd| cd.name.as_ref() == Some(&name))
+                .map(|cd| cd.essential.unwrap_or(true))
+                .unwrap_or(true);
+
+            ContainerOutcome {
+                name,
+                essential,
+                exit_code: container.exit_code,
+                reason: container.reason.to_owned(),
+            }
+        })
+        .collect();
+    // Listed in the order of the definitions, so that the first essential one is the primary.
+    outcomes.sort_by_key(|outcome| {
+        container_definitions
+            .iter()
+            .position(|cd| cd.name.as_ref() == Some(&outcome.name))
+            .unwrap_or(container_definitions.len())
+    });
+
+    Ok(Some(outcomes))
+}
+
+/// Finds why a stopped task failed, checking all the essential containers.
+fn task_failure(task: &rusoto_ecs::Task, containers: &[ContainerOutcome]) -> Option<CommandError> {
+    for container in containers.iter().filter(|container| container.essential) {
+        match container.exit_code {
+            Some(0) => {}
+            Some(code) => {
+                return Some(CommandError::TaskFailed {
+                    exit_code: Some(code),
+                    reason: format!(
+                        "The essential container `{}` exited with non-zero code",
+                        container.name
+                    ),
+                })
+            }
+            None => {
+                return Some(CommandError::TaskFailed {
+                    exit_code: None,
+                    reason: container.reason.to_owned().unwrap_or_else(|| {
+                        format!("The essential container `{}` did not exit", container.name)
+                    }),
+                })
             }
         }
+    }
 
-        // Ok(())
+    if let Some(reason) = task.stopped_reason.as_ref() {
+        if reason != "Essential container in task exited" {
+            return Some(CommandError::TaskFailed {
+                exit_code: None,
+                reason: format!("The task stopped with reason: {}", reason),
+            });
+        }
     }
+
+    None
 }
 
 impl<'c> EcsExecuter for Executer<'c> {
@@ -172,3 +459,60 @@ impl<'c> EcsExecuter for Executer<'c> {
         &self.ecs_client
     }
 }
+
+impl<'c> EcsJsonExecuter for Executer<'c> {
+    fn ecs_json_client(&self) -> &JsonClient {
+        &self.ecs_json_client
+    }
+}
+
+impl<'c> CloudWatchLogsExecuter for Executer<'c> {
+    fn logs_client(&self) -> &CloudWatchLogsClient {
+        &self.logs_client
+    }
+}
+
+impl<'c> MutationExecuter for Executer<'c> {
+    fn dry_run(&self) -> bool {
+        self.dry_run
+    }
+}
+
+#[test]
+fn test_essential_container_failure() {
+    let container = |name: &str, exit_code: i64| rusoto_ecs::Container {
+        name: Some(name.to_owned()),
+        exit_code: Some(exit_code),
+        ..Default::default()
+    };
+    let task = rusoto_ecs::Task {
+        last_status: Some(String::from("STOPPED")),
+        stopped_reason: Some(String::from("Essential container in task exited")),
+        // The sidecar is listed first, but the app is the primary one.
+        containers: Some(vec![container("sidecar", 0), container("app", 3)]),
+        ..Default::default()
+    };
+    let container_definitions = vec![
+        config::ecs::ContainerDefinition {
+            name: Some(String::from("app")),
+            ..Default::default()
+        },
+        config::ecs::ContainerDefinition {
+            name: Some(String::from("sidecar")),
+            essential: Some(false),
+            ..Default::default()
+        },
+    ];
+
+    let containers = check_stopped(&task, &container_definitions)
+        .unwrap()
+        .unwrap();
+    assert_eq!(containers[0].name, "app");
+    assert!(containers[0].essential);
+    assert!(!containers[1].essential);
+
+    match task_failure(&task, &containers) {
+        Some(CommandError::TaskFailed { exit_code, .. }) => assert_eq!(exit_code, Some(3)),
+        _ => panic!("The failure of the essential container should be found"),
+    }
+}
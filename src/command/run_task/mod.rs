@@ -2,4 +2,4 @@ mod command;
 mod executer;
 
 pub use self::command::Command;
-pub use self::executer::Executer;
+pub use self::executer::{Executer, ExecuterOptions};
@@ -2,15 +2,15 @@ use std::error;
 
 use clap;
 
+use crate::command::aws::ClientFactory;
+use crate::command::selector::Selector;
 use crate::config;
 
 use super::executer::Executer;
 
-#[allow(dead_code)]
 pub struct Command<'c> {
     config: &'c config::command::Config,
-    name: Option<&'c str>,
-    all: bool,
+    selector: Selector<'c>,
 }
 
 impl<'c> Command<'c> {
@@ -19,8 +19,7 @@ impl<'c> Command<'c> {
 
         Command {
             config: config,
-            name: args.value_of("NAME"),
-            all: args.is_present("ALL"),
+            selector: Selector::from_args(args),
         }
     }
 
@@ -29,26 +28,42 @@ impl<'c> Command<'c> {
 
         Command {
             config: config,
-            name: name,
-            all: all,
+            selector: Selector::new(name, all),
         }
     }
 
     pub async fn run(&self) -> Result<(), Box<dyn error::Error>> {
         trace!("command::schedule_task::delete::Command::run");
 
-        if let Some(name) = self.name {
-            let schedule_del_exec = Executer::new();
-            schedule_del_exec.run(name).await?;
-        } else if self.all {
-            if let Some(schedule_config_group) = self.config.schedule_task.as_ref() {
-                for schedule_config in schedule_config_group {
-                    let schedule_del_exec = Executer::new();
-                    schedule_del_exec
-                        .run(schedule_config.rule.name.as_str())
-                        .await?;
-                }
+        // Rules are deleted by the names, even if they are no longer in config.
+        if self.selector.is_literal() {
+            for name in self.selector.names().iter() {
+                let entry_aws = self
+                    .config
+                    .schedule_task
+                    .as_ref()
+                    .and_then(|group| group.iter().find(|c| c.rule.name == *name))
+                    .and_then(|c| c.aws.as_ref());
+                let aws_config = self.config.aws_config(entry_aws);
+                let client_factory = ClientFactory::from_config(&aws_config, self.config.dry_run)?;
+                let schedule_del_exec = Executer::new(&client_factory);
+                schedule_del_exec.run(name).await?;
             }
+            return Ok(());
+        }
+
+        let schedule_configs = self.selector.select(
+            self.config,
+            "schedule_task",
+            self.config.schedule_task.as_ref(),
+        )?;
+        for schedule_config in schedule_configs {
+            let aws_config = self.config.aws_config(schedule_config.aws.as_ref());
+            let client_factory = ClientFactory::from_config(&aws_config, self.config.dry_run)?;
+            let schedule_del_exec = Executer::new(&client_factory);
+            schedule_del_exec
+                .run(schedule_config.rule.name.as_str())
+                .await?;
         }
 
         Ok(())
@@ -1,41 +1,47 @@
 use std::error;
 
-use rusoto_core::Region;
 use rusoto_ecs::EcsClient;
 use rusoto_events::EventBridgeClient;
 
+use crate::command::aws::ClientFactory;
 use crate::command::cloudwatch_events::Executer as CloudwatchEventsExecuter;
 use crate::command::ecs::Executer as EcsExecuter;
+use crate::command::mutation::Executer as MutationExecuter;
 use crate::output;
 
 pub struct Executer {
     ecs_client: EcsClient,
     events_client: EventBridgeClient,
+    dry_run: bool,
 }
 
 impl Executer {
-    pub fn new() -> Self {
+    pub fn new(client_factory: &ClientFactory) -> Self {
         trace!("command::schedule_task::delete::Executer::new");
 
-        let ecs_client = EcsClient::new(Region::ApNortheast1);
-        let events_client = EventBridgeClient::new(Region::ApNortheast1);
-
         Executer {
-            ecs_client: ecs_client,
-            events_client: events_client,
+            ecs_client: client_factory.ecs(),
+            events_client: client_factory.events(),
+            dry_run: client_factory.dry_run(),
         }
     }
 
     pub async fn run(&self, rule_name: &str) -> Result<(), Box<dyn error::Error>> {
         trace!("command::schedule_task::delete::Executer::run");
 
-        if self.rule_exists(rule_name).await? {
+        let exists = self.rule_exists(rule_name).await?;
+        if exists {
             self.remove_targets(rule_name).await?;
             self.delete_rule(rule_name).await?;
             output::PrintLine::success("Finished deleting the scheduled task");
         } else {
             output::PrintLine::success("The rule does not exists");
         }
+        output::PrintResult::emit(&json!({
+            "command": "schedule-task delete",
+            "rule": rule_name,
+            "deleted": exists,
+        }));
 
         Ok(())
     }
@@ -52,3 +58,9 @@ impl CloudwatchEventsExecuter for Executer {
         &self.events_client
     }
 }
+
+impl MutationExecuter for Executer {
+    fn dry_run(&self) -> bool {
+        self.dry_run
+    }
+}
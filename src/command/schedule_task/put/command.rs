@@ -2,24 +2,24 @@ use std::error;
 
 use clap;
 
+use crate::command::aws::ClientFactory;
+use crate::command::selector::Selector;
 use crate::config;
 
 use super::executer::Executer;
 
 pub struct Command<'c> {
     config: &'c config::command::Config,
-    name: Option<&'c str>,
-    all: bool,
+    selector: Selector<'c>,
 }
 
 impl<'c> Command<'c> {
     pub fn from_args(config: &'c config::command::Config, args: &'c clap::ArgMatches<'c>) -> Self {
-        trace!("command::schedule_task::put::Command::run");
+        trace!("command::schedule_task::put::Command::from_args");
 
         Command {
             config: config,
-            name: args.value_of("NAME"),
-            all: args.is_present("ALL"),
+            selector: Selector::from_args(args),
         }
     }
 
@@ -28,32 +28,23 @@ impl<'c> Command<'c> {
 
         Command {
             config: config,
-            name: name,
-            all: all,
+            selector: Selector::new(name, all),
         }
     }
 
     pub async fn run(&self) -> Result<(), Box<dyn error::Error>> {
         trace!("command::schedule_task::put::Command::run");
 
-        if let Some(schedule_config_group) = self.config.schedule_task.as_ref() {
-            for schedule_config in schedule_config_group {
-                let mut runnable: bool = false;
-                if let Some(name) = self.name {
-                    if name == schedule_config.name {
-                        runnable = true;
-                    }
-                }
-                if self.all {
-                    runnable = true;
-                }
-                if !runnable {
-                    continue;
-                }
-
-                let schedule_put_exec = Executer::from_config(&schedule_config);
-                schedule_put_exec.run().await?;
-            }
+        let schedule_configs = self.selector.select(
+            self.config,
+            "schedule_task",
+            self.config.schedule_task.as_ref(),
+        )?;
+        for schedule_config in schedule_configs {
+            let aws_config = self.config.aws_config(schedule_config.aws.as_ref());
+            let client_factory = ClientFactory::from_config(&aws_config, self.config.dry_run)?;
+            let schedule_put_exec = Executer::from_config(schedule_config, &client_factory);
+            schedule_put_exec.run().await?;
         }
 
         Ok(())
@@ -1,31 +1,37 @@
 use std::error;
 
-use rusoto_core::Region;
 use rusoto_ecs::EcsClient;
 use rusoto_events::EventBridgeClient;
 
+use crate::command::aws::{ClientFactory, JsonClient};
 use crate::command::cloudwatch_events::Executer as CloudwatchEventsExecuter;
 use crate::command::ecs::Executer as EcsExecuter;
+use crate::command::ecs::JsonExecuter as EcsJsonExecuter;
 use crate::command::error::CommandError;
+use crate::command::mutation::Executer as MutationExecuter;
 use crate::config;
 use crate::output;
 
 pub struct Executer<'c> {
     ecs_client: EcsClient,
+    ecs_json_client: JsonClient,
     events_client: EventBridgeClient,
+    dry_run: bool,
     config: &'c config::command::ScheduleTaskConfig,
 }
 
 impl<'c> Executer<'c> {
-    pub fn from_config(config: &'c config::command::ScheduleTaskConfig) -> Self {
+    pub fn from_config(
+        config: &'c config::command::ScheduleTaskConfig,
+        client_factory: &ClientFactory,
+    ) -> Self {
         trace!("command::schedule_task::put::Executer::from_config");
 
-        let ecs_client = EcsClient::new(Region::ApNortheast1);
-        let events_client = EventBridgeClient::new(Region::ApNortheast1);
-
         Executer {
-            ecs_client: ecs_client,
-            events_client: events_client,
+            ecs_client: client_factory.ecs(),
+            ecs_json_client: client_factory.ecs_json(),
+            events_client: client_factory.events(),
+            dry_run: client_factory.dry_run(),
             config: config,
         }
     }
@@ -34,19 +40,24 @@ impl<'c> Executer<'c> {
         trace!("command::schedule_task::put::Executer::run");
 
         let maybe_ecs_cluster = self.describe_cluster(&self.config.cluster).await?;
-        let ecs_cluster = maybe_ecs_cluster.ok_or(Box::new(CommandError::Unknown))?;
-        let ecs_cluster_arn = ecs_cluster
-            .cluster_arn
-            .as_ref()
-            .ok_or(Box::new(CommandError::Unknown))?;
+        let ecs_cluster = maybe_ecs_cluster.ok_or(CommandError::ClusterNotFound(
+            self.config.cluster.to_owned(),
+        ))?;
+        let ecs_cluster_arn =
+            ecs_cluster
+                .cluster_arn
+                .as_ref()
+                .ok_or(CommandError::UnexpectedResponse {
+                    operation: "DescribeClusters",
+                    detail: "no cluster ARN",
+                })?;
 
         let task_definition = self
             .register_task_definition(&self.config.task_definition)
             .await?;
-        let task_definition_arn = task_definition
-            .task_definition_arn
-            .as_ref()
-            .ok_or(Box::new(CommandError::Unknown))?;
+        let task_definition_arn = task_definition.task_definition_arn.as_ref().ok_or(
+            CommandError::TaskDefinitionNotFound(self.config.task_definition.family.to_owned()),
+        )?;
 
         let role_arn = self
             .config
@@ -59,6 +70,14 @@ impl<'c> Executer<'c> {
             .await?;
 
         output::PrintLine::success("Finished putting the scheduled task");
+        output::PrintResult::emit(&json!({
+            "command": "schedule-task put",
+            "name": self.config.name,
+            "rule": self.config.rule.name,
+            "cluster_arn": ecs_cluster_arn,
+            "task_definition_arn": task_definition_arn,
+            "revision": task_definition.revision,
+        }));
         Ok(())
     }
 }
@@ -69,8 +88,20 @@ impl<'c> EcsExecuter for Executer<'c> {
     }
 }
 
+impl<'c> EcsJsonExecuter for Executer<'c> {
+    fn ecs_json_client(&self) -> &JsonClient {
+        &self.ecs_json_client
+    }
+}
+
 impl<'c> CloudwatchEventsExecuter for Executer<'c> {
     fn events_client(&self) -> &EventBridgeClient {
         &self.events_client
     }
 }
+
+impl<'c> MutationExecuter for Executer<'c> {
+    fn dry_run(&self) -> bool {
+        self.dry_run
+    }
+}
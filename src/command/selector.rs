@@ -0,0 +1,221 @@
+use std::collections::BTreeMap;
+use std::error;
+
+use clap;
+
+use crate::command::error::CommandError;
+use crate::config;
+
+/// An entry of a section in config, which is selected by its name and labels.
+pub trait Entry {
+    fn name(&self) -> &str;
+    fn labels(&self) -> Option<&BTreeMap<String, String>>;
+}
+
+impl Entry for config::command::ServiceConfig {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn labels(&self) -> Option<&BTreeMap<String, String>> {
+        self.labels.as_ref()
+    }
+}
+
+impl Entry for config::command::RunTaskConfig {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn labels(&self) -> Option<&BTreeMap<String, String>> {
+        self.labels.as_ref()
+    }
+}
+
+impl Entry for config::command::ScheduleTaskConfig {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn labels(&self) -> Option<&BTreeMap<String, String>> {
+        self.labels.as_ref()
+    }
+}
+
+/// Selects entries by names, groups and labels given from command line arguments.
+/// Entries matching any of the names or groups are selected, and then narrowed down by the labels.
+pub struct Selector<'c> {
+    names: Vec<&'c str>,
+    groups: Vec<&'c str>,
+    labels: Vec<(&'c str, &'c str)>,
+    all: bool,
+}
+
+impl<'c> Selector<'c> {
+    pub fn from_args(args: &'c clap::ArgMatches<'c>) -> Self {
+        trace!("command::selector::Selector::from_args");
+
+        Selector {
+            names: args
+                .values_of("NAME")
+                .map(|values| values.collect())
+                .unwrap_or_default(),
+            groups: args
+                .values_of("GROUP")
+                .map(|values| values.collect())
+                .unwrap_or_default(),
+            labels: args
+                .values_of("SELECTOR")
+                .map(|values| {
+                    values
+                        .filter_map(|value| {
+                            let mut pair = value.splitn(2, '=');
+                            Some((pair.next()?, pair.next()?))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+            all: args.is_present("ALL"),
+        }
+    }
+
+    pub fn new(name: Option<&'c str>, all: bool) -> Self {
+        trace!("command::selector::Selector::new");
+
+        Selector {
+            names: name.into_iter().collect(),
+            groups: vec![],
+            labels: vec![],
+            all,
+        }
+    }
+
+    /// Tells whether only names without wildcards are given.
+    pub fn is_literal(&self) -> bool {
+        !self.all
+            && self.groups.is_empty()
+            && self.labels.is_empty()
+            && !self.names.iter().any(|name| is_pattern(name))
+    }
+
+    pub fn names(&self) -> &Vec<&'c str> {
+        &self.names
+    }
+
+    pub fn select<'a, E: Entry>(
+        &self,
+        config: &'a config::command::Config,
+        section: &'static str,
+        entries: Option<&'a Vec<E>>,
+    ) -> Result<Vec<&'a E>, Box<dyn error::Error>> {
+        trace!("command::selector::Selector::select");
+
+        let entries: Vec<&E> = entries.into_iter().flatten().collect();
+
+        // A name without wildcards is an error when it is missing, as it is likely a typo.
+        for name in self.names.iter().filter(|name| !is_pattern(name)) {
+            if !entries.iter().any(|entry| entry.name() == *name) {
+                return Err(Box::new(CommandError::ConfigEntryNotFound {
+                    section,
+                    name: (*name).to_owned(),
+                }));
+            }
+        }
+
+        let mut patterns: Vec<&str> = self.names.to_owned();
+        for group in self.groups.iter() {
+            let members = config
+                .groups
+                .as_ref()
+                .and_then(|groups| groups.get(*group))
+                .ok_or(CommandError::ConfigEntryNotFound {
+                    section: "groups",
+                    name: (*group).to_owned(),
+                })?;
+            patterns.extend(members.iter().map(String::as_str));
+        }
+        if !self.all && patterns.is_empty() && self.labels.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let selected: Vec<&E> = entries
+            .into_iter()
+            .filter(|entry| {
+                self.all
+                    || (self.names.is_empty() && self.groups.is_empty())
+                    || patterns
+                        .iter()
+                        .any(|pattern| glob_match(pattern, entry.name()))
+            })
+            .filter(|entry| {
+                self.labels.iter().all(|(key, value)| {
+                    entry
+                        .labels()
+                        .and_then(|labels| labels.get(*key))
+                        .map(String::as_str)
+                        == Some(*value)
+                })
+            })
+            .collect();
+        if selected.is_empty() && !self.all {
+            return Err(Box::new(CommandError::NoEntryMatched {
+                section,
+                selector: self.describe(),
+            }));
+        }
+
+        Ok(selected)
+    }
+
+    fn describe(&self) -> String {
+        let mut terms: Vec<String> = self.names.iter().map(|name| (*name).to_owned()).collect();
+        terms.extend(self.groups.iter().map(|group| format!("--group {}", group)));
+        terms.extend(
+            self.labels
+                .iter()
+                .map(|(key, value)| format!("--selector {}={}", key, value)),
+        );
+        terms.join(" ")
+    }
+}
+
+fn is_pattern(name: &str) -> bool {
+    name.contains('*') || name.contains('?')
+}
+
+/// Matches a name with a pattern, in which `*` matches any characters and `?` matches one.
+fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    let (mut p, mut n) = (0, 0);
+    // The position of the last `*` and of the name where it started matching
+    let mut backtrack: Option<(usize, usize)> = None;
+    while n < name.len() {
+        if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, n));
+            p += 1;
+        } else if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if let Some((star, matched)) = backtrack {
+            backtrack = Some((star, matched + 1));
+            p = star + 1;
+            n = matched + 1;
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[test]
+fn test_glob_match() {
+    assert!(glob_match("cron-*", "cron-daily"));
+    assert!(glob_match("cron-*", "cron-"));
+    assert!(!glob_match("cron-*", "api"));
+    assert!(glob_match("*-worker", "mail-worker"));
+    assert!(glob_match("a*b*c", "aXbYbZc"));
+    assert!(!glob_match("a*b*c", "aXbYbZ"));
+    assert!(glob_match("api-?", "api-1"));
+    assert!(!glob_match("api-?", "api-10"));
+    assert!(glob_match("api", "api"));
+    assert!(!glob_match("api", "api-1"));
+}
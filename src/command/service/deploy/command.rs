@@ -1,16 +1,45 @@
+use std::collections::HashMap;
 use std::error;
+use std::io::Write;
+use std::time::Instant;
+use tabwriter::TabWriter;
 
 use clap;
+use futures::stream::{FuturesUnordered, StreamExt};
 
+use crate::command::aws::ClientFactory;
+use crate::command::error::CommandError;
+use crate::command::run_task;
+use crate::command::selector::Selector;
+use crate::command::wait;
 use crate::config;
+use crate::output;
 
-use super::executer::{Executer, ExecuterOptions};
+use super::executer::{DeployOutcome, Executer, ExecuterOptions};
+
+const DEFAULT_PARALLELISM: usize = 4;
+
+enum DeployState {
+    Pending,
+    Running,
+    Succeeded(f64),
+    AwaitingPromotion(f64),
+    Failed(f64, Box<dyn error::Error>),
+    Skipped(String),
+}
 
 pub struct Command<'c> {
     config: &'c config::command::Config,
-    name: Option<&'c str>,
+    selector: Selector<'c>,
+    parallelism: usize,
+    force_new_deployment: bool,
+    promote: bool,
+    abort: bool,
+    images: Vec<(&'c str, &'c str)>,
     no_wait: bool,
-    all: bool,
+    no_rollback: bool,
+    timeout: Option<u64>,
+    poll_interval: Option<u64>,
 }
 
 impl<'c> Command<'c> {
@@ -19,9 +48,29 @@ impl<'c> Command<'c> {
 
         Command {
             config: config,
-            name: args.value_of("NAME"),
+            selector: Selector::from_args(args),
+            parallelism: args
+                .value_of("PARALLELISM")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_PARALLELISM),
+            force_new_deployment: args.is_present("FORCE_NEW_DEPLOYMENT"),
+            promote: args.is_present("PROMOTE"),
+            abort: args.is_present("ABORT"),
+            images: args
+                .values_of("IMAGE")
+                .map(|values| {
+                    values
+                        .filter_map(|value| {
+                            let mut pair = value.splitn(2, '=');
+                            Some((pair.next()?, pair.next()?))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
             no_wait: args.is_present("NO_WAIT"),
-            all: args.is_present("ALL"),
+            no_rollback: args.is_present("NO_ROLLBACK"),
+            timeout: args.value_of("TIMEOUT").and_then(|v| v.parse().ok()),
+            poll_interval: args.value_of("POLL_INTERVAL").and_then(|v| v.parse().ok()),
         }
     }
 
@@ -35,38 +84,372 @@ impl<'c> Command<'c> {
 
         Command {
             config: config,
-            name: name,
+            selector: Selector::new(name, all),
+            parallelism: DEFAULT_PARALLELISM,
+            force_new_deployment: false,
+            promote: false,
+            abort: false,
+            images: vec![],
             no_wait: no_wait,
-            all: all,
+            no_rollback: false,
+            timeout: None,
+            poll_interval: None,
         }
     }
 
     pub async fn run(&self) -> Result<(), Box<dyn error::Error>> {
         trace!("command::service::deploy::Command::run");
 
-        if let Some(service_config_group) = self.config.service.as_ref() {
-            for service_config in service_config_group {
-                let mut runnable: bool = false;
-                if let Some(name) = self.name {
-                    if name == service_config.name {
-                        runnable = true;
+        let targets = self
+            .selector
+            .select(self.config, "service", self.config.service.as_ref())?;
+
+        // An image is checked against all entries to deploy, since it may be of any of them.
+        for (container_name, _) in self.images.iter() {
+            let found = targets
+                .iter()
+                .flat_map(|c| c.service.task_definition.container_definitions.iter())
+                .any(|cd| cd.name.as_deref() == Some(*container_name));
+            if !found {
+                return Err(Box::new(CommandError::ConfigEntryNotFound {
+                    section: "container_definitions",
+                    name: (*container_name).to_owned(),
+                }));
+            }
+        }
+
+        // A rollout with task sets is driven by racco itself, so it cannot be left running.
+        if self.no_wait {
+            if let Some(c) = targets
+                .iter()
+                .find(|c| c.service.uses_external_controller())
+            {
+                return Err(Box::new(CommandError::InvalidArgument(format!(
+                    "`--no-wait` cannot be used for `{}` of the EXTERNAL deployment controller",
+                    c.name
+                ))));
+            }
+        }
+
+        let states = self.deploy_all(&targets).await;
+        if targets.len() > 1 {
+            output::PrintLine::info("");
+            Self::print(&states)?;
+        }
+
+        // The first failure is returned, so that the exit status tells its kind.
+        for (_, state) in states.into_iter() {
+            if let DeployState::Failed(_, error) = state {
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+
+    /// Deploys the entries concurrently up to the parallelism, after their dependencies succeed.
+    /// Dependencies which are not deployed this time are not waited, and those awaiting
+    /// promotion of CodeDeploy make their dependants skipped.
+    async fn deploy_all(
+        &self,
+        targets: &Vec<&'c config::command::ServiceConfig>,
+    ) -> Vec<(&'c str, DeployState)> {
+        let mut states: HashMap<&str, DeployState> = targets
+            .iter()
+            .map(|c| (c.name.as_str(), DeployState::Pending))
+            .collect();
+        let mut running = FuturesUnordered::new();
+
+        loop {
+            // Skipping an entry may make its dependants skipped, so this is repeated until settled.
+            let mut changed = true;
+            while changed {
+                changed = false;
+                for service_config in targets.iter() {
+                    let name = service_config.name.as_str();
+                    if !matches!(states.get(name), Some(DeployState::Pending)) {
+                        continue;
+                    }
+
+                    let mut ready = true;
+                    let mut failed_dependency = None;
+                    for dependency in service_config.depends_on.iter().flatten() {
+                        match states.get(dependency.as_str()) {
+                            Some(DeployState::Failed(..))
+                            | Some(DeployState::AwaitingPromotion(_))
+                            | Some(DeployState::Skipped(_)) => {
+                                failed_dependency = Some(dependency.to_owned());
+                            }
+                            Some(DeployState::Pending) | Some(DeployState::Running) => {
+                                ready = false;
+                            }
+                            Some(DeployState::Succeeded(_)) | None => {}
+                        }
+                    }
+
+                    if let Some(dependency) = failed_dependency {
+                        output::PrintLine::warn(&format!(
+                            "Skipping `{}`, since `{}` was not deployed",
+                            name, dependency
+                        ));
+                        states.insert(name, DeployState::Skipped(dependency));
+                        changed = true;
+                    } else if ready && running.len() < self.parallelism.max(1) {
+                        states.insert(name, DeployState::Running);
+                        running.push(self.deploy(service_config, targets.len() > 1));
+                        changed = true;
                     }
                 }
-                if self.all {
-                    runnable = true;
+            }
+
+            match running.next().await {
+                Some((name, started_at, result)) => {
+                    let elapsed = Instant::now().duration_since(started_at).as_secs_f64();
+                    let state = match result {
+                        Ok(DeployOutcome::Completed) => DeployState::Succeeded(elapsed),
+                        Ok(DeployOutcome::AwaitingPromotion) => {
+                            DeployState::AwaitingPromotion(elapsed)
+                        }
+                        Err(error) => DeployState::Failed(elapsed, error),
+                    };
+                    states.insert(name, state);
                 }
-                if !runnable {
-                    continue;
+                None => break,
+            }
+        }
+
+        targets
+            .iter()
+            .filter_map(|c| {
+                states
+                    .remove(c.name.as_str())
+                    .map(|state| (c.name.as_str(), state))
+            })
+            .collect()
+    }
+
+    // Lines of an entry are prefixed with its name, when entries are deployed concurrently.
+    async fn deploy(
+        &self,
+        service_config: &'c config::command::ServiceConfig,
+        prefixed: bool,
+    ) -> (
+        &'c str,
+        Instant,
+        Result<DeployOutcome, Box<dyn error::Error>>,
+    ) {
+        let started_at = Instant::now();
+        let result = if prefixed {
+            output::PrintLine::with_prefix(
+                &service_config.name,
+                self.deploy_service(service_config),
+            )
+            .await
+        } else {
+            self.deploy_service(service_config).await
+        };
+        (service_config.name.as_str(), started_at, result)
+    }
+
+    async fn deploy_service(
+        &self,
+        service_config: &config::command::ServiceConfig,
+    ) -> Result<DeployOutcome, Box<dyn error::Error>> {
+        output::PrintLine::info(&format!("Deploying `{}`", service_config.name));
+
+        let (timeout, poll_interval) = wait::durations(
+            self.timeout,
+            self.poll_interval,
+            service_config.timeout,
+            service_config.poll_interval,
+        );
+        let options = ExecuterOptions {
+            force_new_deployment: self.force_new_deployment,
+            promote: self.promote,
+            abort: self.abort,
+            images: self
+                .images
+                .iter()
+                .map(|(container_name, image)| ((*container_name).to_owned(), (*image).to_owned()))
+                .collect(),
+            no_wait: self.no_wait,
+            no_rollback: self.no_rollback,
+            timeout,
+            poll_interval,
+        };
+        // Hooks run around a deployment of a new task definition, neither when acting on
+        // the one in progress nor when restarting the current one.
+        let runs_hooks = !self.promote && !self.abort && !self.force_new_deployment;
+
+        if runs_hooks {
+            for hook in service_config.before_deploy.iter().flatten() {
+                self.run_hook(service_config, "before_deploy", hook).await?;
+            }
+        }
+
+        let aws_config = self.config.aws_config(service_config.aws.as_ref());
+        let client_factory = ClientFactory::from_config(&aws_config, self.config.dry_run)?;
+        let ecs_deploy_cmd = Executer::from_config(service_config, &client_factory, &options);
+        let outcome = ecs_deploy_cmd.run().await?;
+
+        if !runs_hooks {
+            return Ok(outcome);
+        }
+        if service_config.after_deploy.is_some() && self.no_wait {
+            // The new tasks may not be running yet.
+            output::PrintLine::warn("Skipping `after_deploy` hooks, since `--no-wait` is given");
+            return Ok(outcome);
+        }
+        if service_config.after_deploy.is_some() && outcome == DeployOutcome::AwaitingPromotion {
+            // The traffic is not rerouted to the new tasks yet.
+            output::PrintLine::warn(
+                "Skipping `after_deploy` hooks, since the deployment is awaiting promotion",
+            );
+            return Ok(outcome);
+        }
+        for hook in service_config.after_deploy.iter().flatten() {
+            self.run_hook(service_config, "after_deploy", hook).await?;
+        }
+
+        Ok(outcome)
+    }
+
+    /// Runs the task of a hook and waits for it to succeed.
+    /// Containers named as in the service run the image being deployed.
+    async fn run_hook(
+        &self,
+        service_config: &config::command::ServiceConfig,
+        phase: &'static str,
+        hook: &config::command::HookConfig,
+    ) -> Result<(), Box<dyn error::Error>> {
+        trace!("command::service::deploy::Command::run_hook");
+
+        let mut run_task_config = match hook {
+            config::command::HookConfig::Name(name) => self
+                .config
+                .run_task
+                .iter()
+                .flatten()
+                .find(|c| &c.name == name)
+                .ok_or(CommandError::ConfigEntryNotFound {
+                    section: "run_task",
+                    name: name.to_owned(),
+                })?
+                .to_owned(),
+            config::command::HookConfig::Task(run_task_config) => {
+                let mut run_task_config = run_task_config.as_ref().to_owned();
+                // Inline hooks run on the same account as the service by default.
+                if run_task_config.aws.is_none() {
+                    run_task_config.aws = service_config.aws.to_owned();
                 }
+                run_task_config
+            }
+        };
 
-                let options = ExecuterOptions {
-                    no_wait: self.no_wait,
-                };
-                let ecs_deploy_cmd = Executer::from_config(&service_config, &options);
-                ecs_deploy_cmd.run().await?;
+        let service_container_definitions =
+            &service_config.service.task_definition.container_definitions;
+        for cd in run_task_config
+            .task_definition
+            .container_definitions
+            .iter_mut()
+        {
+            let name = match cd.name.as_deref() {
+                Some(name) => name,
+                None => continue,
+            };
+            let image = self
+                .images
+                .iter()
+                .find(|(container_name, _)| *container_name == name)
+                .map(|(_, image)| (*image).to_owned())
+                .or_else(|| {
+                    service_container_definitions
+                        .iter()
+                        .find(|scd| scd.name.as_deref() == Some(name))
+                        .and_then(|scd| scd.image.to_owned())
+                });
+            if image.is_some() {
+                cd.image = image;
             }
         }
 
+        output::PrintLine::info(&format!(
+            "Running the hook `{}` of `{}`",
+            run_task_config.name, service_config.name
+        ));
+        let (timeout, poll_interval) = wait::durations(
+            None,
+            None,
+            run_task_config.timeout,
+            run_task_config.poll_interval,
+        );
+        let options = run_task::ExecuterOptions {
+            overrides: run_task_config.overrides.to_owned(),
+            count: None,
+            no_wait: false,
+            no_logs: false,
+            propagate_exit_code: false,
+            timeout,
+            poll_interval,
+        };
+        let aws_config = self.config.aws_config(run_task_config.aws.as_ref());
+        let client_factory = ClientFactory::from_config(&aws_config, self.config.dry_run)?;
+        run_task::Executer::from_config(&run_task_config, &client_factory, &options)
+            .run()
+            .await
+            .map_err(|e| {
+                Box::new(CommandError::HookFailed {
+                    phase,
+                    hook: run_task_config.name.to_owned(),
+                    source: e,
+                }) as Box<dyn error::Error>
+            })
+    }
+
+    /// Prints a summary of each entry, which is also emitted as a result in JSON output.
+    fn print(states: &Vec<(&str, DeployState)>) -> Result<(), Box<dyn error::Error>> {
+        let mut tw = TabWriter::new(output::text_writer());
+
+        writeln!(&mut tw, "SERVICE\tSTATUS\tELAPSED\tREASON")?;
+        for (name, state) in states.iter() {
+            let (status, elapsed, reason) = match state {
+                DeployState::Succeeded(elapsed) => ("SUCCEEDED", Some(*elapsed), None),
+                DeployState::AwaitingPromotion(elapsed) => (
+                    "AWAITING_PROMOTION",
+                    Some(*elapsed),
+                    Some(String::from(
+                        "Run `service deploy --promote` to reroute the traffic",
+                    )),
+                ),
+                DeployState::Failed(elapsed, error) => {
+                    ("FAILED", Some(*elapsed), Some(error.to_string()))
+                }
+                DeployState::Skipped(dependency) => (
+                    "SKIPPED",
+                    None,
+                    Some(format!("The dependency `{}` was not deployed", dependency)),
+                ),
+                DeployState::Pending | DeployState::Running => ("PENDING", None, None),
+            };
+            output::PrintResult::emit(&json!({
+                "command": "service deploy",
+                "name": name,
+                "status": status.to_lowercase(),
+                "elapsed_seconds": elapsed,
+                "reason": reason,
+            }));
+            writeln!(
+                &mut tw,
+                "{}\t{}\t{}\t{}",
+                name,
+                status,
+                elapsed
+                    .map(|elapsed| format!("{:.0}s", elapsed))
+                    .unwrap_or_default(),
+                reason.unwrap_or_default()
+            )?;
+        }
+
+        tw.flush()?;
         Ok(())
     }
 }
@@ -1,22 +1,51 @@
 use std::error;
-use std::thread::sleep;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use rusoto_core::Region;
+use rusoto_codedeploy::CodeDeployClient;
 use rusoto_ecs;
 use rusoto_ecs::EcsClient;
+use tokio::time::sleep;
 
+use crate::command::aws::{ClientFactory, JsonClient};
+use crate::command::codedeploy;
+use crate::command::codedeploy::Executer as CodeDeployExecuter;
+use crate::command::ecs;
 use crate::command::ecs::Executer as EcsExecuter;
+use crate::command::ecs::JsonExecuter as EcsJsonExecuter;
 use crate::command::error::CommandError;
+use crate::command::mutation::Executer as MutationExecuter;
+use crate::command::service::Executer as ServiceExecuter;
+use crate::command::wait;
 use crate::config;
 use crate::output;
 
+/// How long a rollout with task sets waits at each step, unless `wait_seconds` is given.
+const DEFAULT_ROLLOUT_WAIT: Duration = Duration::from_secs(60);
+
+/// How a deployment ended, when it did not fail.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DeployOutcome {
+    Completed,
+    /// The new tasks of CodeDeploy are ready, but the traffic waits for `--promote`.
+    AwaitingPromotion,
+}
+
 pub struct ExecuterOptions {
+    pub force_new_deployment: bool,
+    pub images: Vec<(String, String)>,
+    pub promote: bool,
+    pub abort: bool,
     pub no_wait: bool,
+    pub no_rollback: bool,
+    pub timeout: Option<Duration>,
+    pub poll_interval: Duration,
 }
 
 pub struct Executer<'c> {
     ecs_client: EcsClient,
+    ecs_json_client: JsonClient,
+    codedeploy_client: CodeDeployClient,
+    dry_run: bool,
     config: &'c config::command::ServiceConfig,
     options: &'c ExecuterOptions,
 }
@@ -24,54 +53,102 @@ pub struct Executer<'c> {
 impl<'c> Executer<'c> {
     pub fn from_config(
         config: &'c config::command::ServiceConfig,
+        client_factory: &ClientFactory,
         options: &'c ExecuterOptions,
     ) -> Self {
         trace!("command::service::deploy::Executer::from_config");
 
-        let client = EcsClient::new(Region::ApNortheast1);
         Executer {
-            ecs_client: client,
+            ecs_client: client_factory.ecs(),
+            ecs_json_client: client_factory.ecs_json(),
+            codedeploy_client: client_factory.codedeploy(),
+            dry_run: client_factory.dry_run(),
             config: config,
             options: options,
         }
     }
 
-    pub async fn run(&self) -> Result<(), Box<dyn error::Error>> {
+    pub async fn run(&self) -> Result<DeployOutcome, Box<dyn error::Error>> {
         trace!("command::service::deploy::Executer::run");
 
+        let started_at = Instant::now();
         let service_conf = &self.config.service;
         let cluster = &self.config.cluster;
 
+        if self.options.force_new_deployment {
+            return self.force_new_deployment(started_at).await;
+        }
+        if self.options.promote || self.options.abort {
+            return self.control_code_deploy(started_at).await;
+        }
+
+        let task_definition_conf = self.task_definition_conf();
+        // Described in JSON, so that `runtimePlatform` is compared without another request.
         let maybe_latest_task_definition = self
-            .describe_latest_task_definition(&service_conf.task_definition.family)
+            .describe_task_definition_json(&task_definition_conf.family)
             .await?;
 
-        let task_definition = if let Some(latest_task_definition) = maybe_latest_task_definition {
-            if self.detect_task_definition_changes(
-                &service_conf.task_definition,
-                &latest_task_definition,
-            ) {
-                output::PrintLine::info("Registering a task definition");
-                self.register_task_definition(&service_conf.task_definition)
-                    .await?
-            } else {
+        let reusable_task_definition = match maybe_latest_task_definition {
+            Some((latest, runtime_platform))
+                if !self.detect_task_definition_changes(&task_definition_conf, &latest)
+                    && ecs::runtime_platform_change(
+                        &task_definition_conf,
+                        runtime_platform.as_ref(),
+                    )
+                    .is_none() =>
+            {
+                Some(latest)
+            }
+            _ => None,
+        };
+        let registered = reusable_task_definition.is_none();
+
+        let task_definition = match reusable_task_definition {
+            Some(latest_task_definition) => {
+                output::PrintLine::info(&format!(
+                    "No changes in the task definition. Using the current revision: {}",
+                    latest_task_definition.revision.unwrap_or(0)
+                ));
                 latest_task_definition
             }
-        } else {
-            output::PrintLine::info("Registering a task definition");
-            self.register_task_definition(&service_conf.task_definition)
-                .await?
+            None => {
+                output::PrintLine::info("Registering a task definition");
+                self.register_task_definition(&task_definition_conf).await?
+            }
         };
 
-        let task_definition_arn = task_definition
-            .task_definition_arn
-            .as_ref()
-            .ok_or(Box::new(CommandError::Unknown))?;
+        let task_definition_arn = task_definition.task_definition_arn.as_ref().ok_or(
+            CommandError::TaskDefinitionNotFound(service_conf.task_definition.family.to_owned()),
+        )?;
 
         let maybe_service = self.describe_service(cluster, &service_conf).await?;
+        let previous_task_definition_arn = maybe_service
+            .as_ref()
+            .and_then(|s| s.task_definition.to_owned());
+        // Nothing is deployed when the task definition is reused and the service already runs it.
+        let up_to_date = !registered
+            && maybe_service
+                .as_ref()
+                .and_then(|s| Self::current_task_definition_arn(service_conf, s))
+                == Some(task_definition_arn);
+
+        let created = maybe_service.is_none();
 
         let _service: rusoto_ecs::Service = match maybe_service {
-            Some(s) => s,
+            Some(s) => {
+                let recreation_changes = ecs::recreation_changes(service_conf, Some(&s));
+                if !recreation_changes.is_empty() {
+                    output::PrintLine::warn(&format!(
+                        "These fields cannot be changed without recreating the service: {}",
+                        recreation_changes
+                            .iter()
+                            .map(|change| change.path.as_str())
+                            .collect::<Vec<&str>>()
+                            .join(", ")
+                    ));
+                }
+                s
+            }
             None => {
                 output::PrintLine::info("Service has not been exist. Creating...");
                 self.create_service(cluster, &service_conf, &task_definition_arn)
@@ -79,64 +156,706 @@ impl<'c> Executer<'c> {
             }
         };
 
+        if service_conf.uses_external_controller() {
+            return self
+                .roll_out(started_at, &task_definition, registered, up_to_date)
+                .await;
+        }
+
+        // A service created with the CodeDeploy controller starts with the task definition.
+        if service_conf.uses_code_deploy() && !created {
+            return self
+                .code_deploy(started_at, &task_definition, registered, up_to_date)
+                .await;
+        }
+
         output::PrintLine::info("Starting to update the service");
-        self.update_service(cluster, &service_conf, &task_definition)
+        let service = self
+            .update_service_json(cluster, service_conf, &task_definition, false)
             .await?;
         output::PrintLine::info("Finished updating the service");
 
-        if !self.options.no_wait {
-            self.wait_for_green(&service_conf).await?;
+        // The new tasks of a CodeDeploy service are in its primary task set, not in a deployment.
+        if !self.options.no_wait && !self.dry_run && service_conf.uses_code_deploy() {
+            self.wait_for_primary_task_set().await?;
+        } else if !self.options.no_wait && !self.dry_run {
+            if let Err(err) = self.wait_for_green(task_definition_arn).await {
+                if Self::is_deployment_failure(err.as_ref()) && !self.options.no_rollback {
+                    self.rollback(previous_task_definition_arn.as_ref(), task_definition_arn)
+                        .await;
+                }
+                return Err(err);
+            }
         }
 
         output::PrintLine::success("Deployment completed");
-        Ok(())
+        output::PrintResult::emit(&json!({
+            "command": "service deploy",
+            "name": self.config.name,
+            "cluster": cluster,
+            "service_arn": service.service_arn,
+            "task_definition_arn": task_definition_arn,
+            "revision": task_definition.revision,
+            "registered": registered,
+            "elapsed_seconds": started_at.elapsed().as_secs_f64(),
+        }));
+        Ok(DeployOutcome::Completed)
     }
 
-    async fn wait_for_green(
+    /// Returns the task definition in config, with the images given from the options.
+    fn task_definition_conf(&self) -> config::ecs::TaskDefinition {
+        let mut task_definition = self.config.service.task_definition.to_owned();
+
+        for (container_name, image) in self.options.images.iter() {
+            for cd in task_definition.container_definitions.iter_mut() {
+                if cd.name.as_ref() == Some(container_name) {
+                    output::PrintLine::info(&format!(
+                        "Using the image of `{}`: {}",
+                        container_name, image
+                    ));
+                    cd.image = Some(image.to_owned());
+                }
+            }
+        }
+
+        task_definition
+    }
+
+    /// Starts new tasks with the task definition the service is running, without registering.
+    async fn force_new_deployment(
         &self,
+        started_at: Instant,
+    ) -> Result<DeployOutcome, Box<dyn error::Error>> {
+        trace!("command::service::deploy::Executer::force_new_deployment");
+
+        let service_conf = &self.config.service;
+        let cluster = &self.config.cluster;
+
+        let maybe_service = self.describe_service(cluster, service_conf).await?;
+        let service = maybe_service.ok_or(CommandError::ServiceNotFound {
+            cluster: cluster.to_owned(),
+            name: service_conf.name.to_owned(),
+        })?;
+        let current_arn = Self::current_task_definition_arn(service_conf, &service).ok_or(
+            CommandError::UnexpectedResponse {
+                operation: "DescribeServices",
+                detail: "no task definition of the service",
+            },
+        )?;
+        let task_definition = self
+            .describe_task_definition(current_arn)
+            .await?
+            .ok_or(CommandError::TaskDefinitionNotFound(current_arn.to_owned()))?;
+
+        output::PrintLine::info(&format!(
+            "Forcing a new deployment with the current revision: {}",
+            task_definition.revision.unwrap_or(0)
+        ));
+        if service_conf.uses_code_deploy() {
+            return self
+                .code_deploy(started_at, &task_definition, false, false)
+                .await;
+        }
+        if service_conf.uses_external_controller() {
+            return self
+                .roll_out(started_at, &task_definition, false, false)
+                .await;
+        }
+        let service = self
+            .update_service_json(cluster, service_conf, &task_definition, true)
+            .await?;
+        output::PrintLine::info("Finished updating the service");
+
+        // There is nothing to roll back to, since the task definition is not changed.
+        if !self.options.no_wait && !self.dry_run {
+            self.wait_for_green(current_arn).await?;
+        }
+
+        output::PrintLine::success("Deployment completed");
+        output::PrintResult::emit(&json!({
+            "command": "service deploy",
+            "name": self.config.name,
+            "cluster": cluster,
+            "service_arn": service.service_arn,
+            "task_definition_arn": current_arn,
+            "revision": task_definition.revision,
+            "registered": false,
+            "force_new_deployment": true,
+            "elapsed_seconds": started_at.elapsed().as_secs_f64(),
+        }));
+        Ok(DeployOutcome::Completed)
+    }
+
+    // A service of the EXTERNAL controller runs the task definition of its primary task set.
+    fn current_task_definition_arn<'s>(
         service_conf: &config::ecs::Service,
+        service: &'s rusoto_ecs::Service,
+    ) -> Option<&'s String> {
+        if service_conf.uses_external_controller() {
+            service
+                .task_sets
+                .iter()
+                .flatten()
+                .find(|task_set| task_set.status.as_deref() == Some("PRIMARY"))
+                .and_then(|task_set| task_set.task_definition.as_ref())
+        } else {
+            service.task_definition.as_ref()
+        }
+    }
+
+    fn deployment_conf(&self) -> Result<&config::codedeploy::Deployment, Box<dyn error::Error>> {
+        self.config
+            .code_deploy
+            .as_ref()
+            .ok_or(Box::new(CommandError::ConfigEntryNotFound {
+                section: "code_deploy",
+                name: self.config.name.to_owned(),
+            }))
+    }
+
+    /// Deploys the task definition through CodeDeploy, which shifts the traffic to a new task set.
+    /// No deployment is created when the service is up to date with it.
+    async fn code_deploy(
+        &self,
+        started_at: Instant,
+        task_definition: &rusoto_ecs::TaskDefinition,
+        registered: bool,
+        up_to_date: bool,
+    ) -> Result<DeployOutcome, Box<dyn error::Error>> {
+        trace!("command::service::deploy::Executer::code_deploy");
+
+        let service_conf = &self.config.service;
+        let cluster = &self.config.cluster;
+        let deployment_conf = self.deployment_conf()?;
+        let task_definition_arn = task_definition.task_definition_arn.as_ref().ok_or(
+            CommandError::TaskDefinitionNotFound(service_conf.task_definition.family.to_owned()),
+        )?;
+
+        // The settings other than the task definition are still changed by UpdateService.
+        output::PrintLine::info("Starting to update the service");
+        let service = self
+            .update_service_json(cluster, service_conf, task_definition, false)
+            .await?;
+
+        if up_to_date {
+            output::PrintLine::success(
+                "The service is already running the task definition. No deployment is created",
+            );
+            output::PrintResult::emit(&json!({
+                "command": "service deploy",
+                "name": self.config.name,
+                "cluster": cluster,
+                "service_arn": service.service_arn,
+                "task_definition_arn": task_definition_arn,
+                "revision": task_definition.revision,
+                "registered": registered,
+                "deployment_id": null,
+                "elapsed_seconds": started_at.elapsed().as_secs_f64(),
+            }));
+            return Ok(DeployOutcome::Completed);
+        }
+
+        output::PrintLine::info("Creating a deployment of CodeDeploy");
+        let app_spec = codedeploy::app_spec(service_conf, deployment_conf, task_definition_arn);
+        let deployment_id = self.create_deployment(deployment_conf, &app_spec).await?;
+        let status = match deployment_id.as_ref() {
+            Some(id) => {
+                output::PrintLine::info(&format!("Created the deployment: {}", id));
+                if self.options.no_wait {
+                    None
+                } else {
+                    Some(self.wait_for_code_deploy(id).await?)
+                }
+            }
+            None => None,
+        };
+
+        let outcome = Self::report_code_deploy(status.as_deref());
+        output::PrintResult::emit(&json!({
+            "command": "service deploy",
+            "name": self.config.name,
+            "cluster": cluster,
+            "service_arn": service.service_arn,
+            "task_definition_arn": task_definition_arn,
+            "revision": task_definition.revision,
+            "registered": registered,
+            "deployment_id": deployment_id,
+            "deployment_status": status,
+            "elapsed_seconds": started_at.elapsed().as_secs_f64(),
+        }));
+        Ok(outcome)
+    }
+
+    /// Promotes or aborts the deployment of CodeDeploy in progress.
+    /// Promoting reroutes the traffic to the new tasks, or ends the wait before terminating the
+    /// original tasks if it is already rerouted. Aborting stops it and rolls it back.
+    async fn control_code_deploy(
+        &self,
+        started_at: Instant,
+    ) -> Result<DeployOutcome, Box<dyn error::Error>> {
+        trace!("command::service::deploy::Executer::control_code_deploy");
+
+        let deployment_conf = self.deployment_conf()?;
+        let deployment_id = self.active_deployment(deployment_conf).await?.ok_or(
+            CommandError::DeploymentNotFound(format!(
+                "{}/{}",
+                deployment_conf.application_name, deployment_conf.deployment_group_name
+            )),
+        )?;
+
+        let (status, outcome) = if self.options.abort {
+            output::PrintLine::info(&format!(
+                "Stopping the deployment {} to roll back",
+                deployment_id
+            ));
+            self.stop_deployment(&deployment_id, true).await?;
+            output::PrintLine::success("Stopped the deployment");
+            (None, DeployOutcome::Completed)
+        } else {
+            let deployment = self.get_deployment(&deployment_id).await?;
+            let wait_type = if deployment.status.as_deref() == Some("Ready") {
+                "READY_WAIT"
+            } else {
+                "TERMINATION_WAIT"
+            };
+            output::PrintLine::info(&format!(
+                "Continuing the deployment {} ({})",
+                deployment_id, wait_type
+            ));
+            self.continue_deployment(&deployment_id, wait_type).await?;
+
+            let status = if self.options.no_wait || self.dry_run {
+                None
+            } else {
+                Some(self.wait_for_code_deploy(&deployment_id).await?)
+            };
+            let outcome = Self::report_code_deploy(status.as_deref());
+            (status, outcome)
+        };
+
+        output::PrintResult::emit(&json!({
+            "command": "service deploy",
+            "name": self.config.name,
+            "cluster": self.config.cluster,
+            "deployment_id": deployment_id,
+            "action": if self.options.abort { "abort" } else { "promote" },
+            "deployment_status": status,
+            "elapsed_seconds": started_at.elapsed().as_secs_f64(),
+        }));
+        Ok(outcome)
+    }
+
+    // When it times out, the deployment is stopped to be rolled back by CodeDeploy,
+    // in place of switching the task definition of the service.
+    async fn wait_for_code_deploy(
+        &self,
+        deployment_id: &str,
+    ) -> Result<String, Box<dyn error::Error>> {
+        trace!("command::service::deploy::Executer::wait_for_code_deploy");
+
+        let target_id = format!("{}:{}", self.config.cluster, self.config.service.name);
+        let res = wait::with_timeout(
+            self.options.timeout,
+            self.poll_deployment(deployment_id, &target_id, self.options.poll_interval),
+        )
+        .await;
+
+        if let Err(err) = res.as_ref() {
+            let timed_out = matches!(
+                CommandError::find(err.as_ref()),
+                Some(CommandError::Timeout(_))
+            );
+            if timed_out && !self.options.no_rollback {
+                output::PrintLine::info("The deployment timed out. Stopping it to roll back");
+                if let Err(e) = self.stop_deployment(deployment_id, true).await {
+                    output::PrintLine::error(&format!("Failed stopping the deployment: {}", e));
+                }
+            }
+        }
+        res
+    }
+
+    // A deployment ready for the traffic to be rerouted is not completed until it is promoted.
+    fn report_code_deploy(status: Option<&str>) -> DeployOutcome {
+        match status {
+            Some("Ready") => {
+                output::PrintLine::success(
+                    "The new tasks are ready. Run `service deploy --promote` to reroute the traffic to them",
+                );
+                DeployOutcome::AwaitingPromotion
+            }
+            Some(_) => {
+                output::PrintLine::success("Deployment completed");
+                DeployOutcome::Completed
+            }
+            None => {
+                output::PrintLine::success("Deployment created");
+                DeployOutcome::Completed
+            }
+        }
+    }
+
+    /// Waits for the primary task set of the service to be stable,
+    /// which a service of the CodeDeploy controller is created with.
+    async fn wait_for_primary_task_set(&self) -> Result<(), Box<dyn error::Error>> {
+        trace!("command::service::deploy::Executer::wait_for_primary_task_set");
+
+        let cluster = &self.config.cluster;
+        let service_conf = &self.config.service;
+
+        let service = self.describe_service(cluster, service_conf).await?.ok_or(
+            CommandError::ServiceNotFound {
+                cluster: cluster.to_owned(),
+                name: service_conf.name.to_owned(),
+            },
+        )?;
+        let task_set_id = service
+            .task_sets
+            .unwrap_or_default()
+            .into_iter()
+            .find(|task_set| task_set.status.as_deref() == Some("PRIMARY"))
+            .and_then(|task_set| task_set.id)
+            .ok_or(CommandError::UnexpectedResponse {
+                operation: "DescribeServices",
+                detail: "no primary task set of the service",
+            })?;
+        wait::with_timeout(self.options.timeout, self.poll_task_set(&task_set_id)).await
+    }
+
+    /// Rolls out the task definition with a new task set, shifting its scale in the steps of
+    /// `rollout` while scaling the other task sets down. After the last step the new task set
+    /// becomes the primary one, and the others are deleted.
+    /// Nothing is rolled out when the service is up to date with the task definition.
+    async fn roll_out(
+        &self,
+        started_at: Instant,
+        task_definition: &rusoto_ecs::TaskDefinition,
+        registered: bool,
+        up_to_date: bool,
+    ) -> Result<DeployOutcome, Box<dyn error::Error>> {
+        trace!("command::service::deploy::Executer::roll_out");
+
+        let service_conf = &self.config.service;
+        let cluster = &self.config.cluster;
+        let rollout = service_conf
+            .rollout()
+            .ok_or(CommandError::ConfigEntryNotFound {
+                section: "rollout",
+                name: self.config.name.to_owned(),
+            })?;
+        let task_definition_arn = task_definition.task_definition_arn.as_ref().ok_or(
+            CommandError::TaskDefinitionNotFound(service_conf.task_definition.family.to_owned()),
+        )?;
+
+        // The settings other than the task definition are still changed by UpdateService.
+        output::PrintLine::info("Starting to update the service");
+        let service = self
+            .update_service_json(cluster, service_conf, task_definition, false)
+            .await?;
+
+        if up_to_date {
+            output::PrintLine::success(
+                "The primary task set is already running the task definition. Nothing is rolled out",
+            );
+            output::PrintResult::emit(&json!({
+                "command": "service deploy",
+                "name": self.config.name,
+                "cluster": cluster,
+                "service_arn": service.service_arn,
+                "task_definition_arn": task_definition_arn,
+                "revision": task_definition.revision,
+                "registered": registered,
+                "task_set_id": null,
+                "elapsed_seconds": started_at.elapsed().as_secs_f64(),
+            }));
+            return Ok(DeployOutcome::Completed);
+        }
+
+        let previous_task_sets: Vec<String> = self
+            .describe_service(cluster, service_conf)
+            .await?
+            .and_then(|s| s.task_sets)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|task_set| task_set.status.as_deref() != Some("DRAINING"))
+            .filter_map(|task_set| task_set.id)
+            .collect();
+
+        let steps = if previous_task_sets.is_empty() {
+            output::PrintLine::info("No task set to shift from. Starting the new one at 100%");
+            vec![100.0]
+        } else {
+            rollout.steps.to_owned()
+        };
+        let step_wait = rollout
+            .wait_seconds
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_ROLLOUT_WAIT);
+
+        // `--timeout` bounds the whole rollout, including the waits between the steps.
+        let deadline = wait::Deadline::after(self.options.timeout);
+
+        output::PrintLine::info(&format!("Creating a task set at {}%", steps[0]));
+        let task_set = self
+            .create_task_set(cluster, service_conf, task_definition_arn, steps[0])
+            .await?;
+        let task_set_id = task_set.id.ok_or(CommandError::UnexpectedResponse {
+            operation: "CreateTaskSet",
+            detail: "no task set ID",
+        })?;
+        output::PrintLine::info(&format!("Created the task set: {}", task_set_id));
+
+        if let Err(err) = self
+            .shift_task_sets(
+                &task_set_id,
+                &previous_task_sets,
+                &steps,
+                step_wait,
+                &deadline,
+            )
+            .await
+        {
+            if Self::is_deployment_failure(err.as_ref()) && !self.options.no_rollback {
+                self.roll_back_task_sets(&task_set_id, &previous_task_sets)
+                    .await;
+            }
+            return Err(err);
+        }
+
+        output::PrintLine::info("Making the new task set primary");
+        self.update_service_primary_task_set(cluster, &service_conf.name, &task_set_id)
+            .await?;
+        for id in previous_task_sets.iter() {
+            output::PrintLine::info(&format!("Deleting the previous task set: {}", id));
+            self.delete_task_set(cluster, &service_conf.name, id)
+                .await?;
+        }
+
+        output::PrintLine::success("Deployment completed");
+        output::PrintResult::emit(&json!({
+            "command": "service deploy",
+            "name": self.config.name,
+            "cluster": cluster,
+            "service_arn": service.service_arn,
+            "task_definition_arn": task_definition_arn,
+            "revision": task_definition.revision,
+            "registered": registered,
+            "task_set_id": task_set_id,
+            "steps": steps,
+            "elapsed_seconds": started_at.elapsed().as_secs_f64(),
+        }));
+        Ok(DeployOutcome::Completed)
+    }
+
+    // At each step, the previous task sets are scaled down only after the new one gets stable,
+    // and the new one has to stay stable through the wait before the next step.
+    async fn shift_task_sets(
+        &self,
+        task_set_id: &str,
+        previous_task_sets: &[String],
+        steps: &[f64],
+        step_wait: Duration,
+        deadline: &wait::Deadline,
     ) -> Result<(), Box<dyn error::Error>> {
-        trace!("command::service::deploy::Executer::wait_for_green");
+        trace!("command::service::deploy::Executer::shift_task_sets");
+
         let cluster = &self.config.cluster;
+        let service_name = &self.config.service.name;
 
-        // TODO: Timeout
-        loop {
-            let maybe_service = self.describe_service(cluster, service_conf).await?;
-            let service = maybe_service.ok_or(Box::new(CommandError::Unknown))?;
-
-            let maybe_primary = service.deployments.as_ref().and_then(|deployments| {
-                deployments
-                    .iter()
-                    .filter(|deployment| match deployment.status.as_ref() {
-                        Some(s) => s == "PRIMARY",
-                        _ => false,
+        for (i, step) in steps.iter().enumerate() {
+            if i > 0 {
+                output::PrintLine::info(&format!("Scaling the new task set to {}%", step));
+                self.update_task_set_scale(cluster, service_name, task_set_id, *step)
+                    .await?;
+            }
+            if !self.dry_run {
+                deadline.run(self.poll_task_set(task_set_id)).await?;
+            }
+            for id in previous_task_sets.iter() {
+                self.update_task_set_scale(cluster, service_name, id, 100.0 - step)
+                    .await?;
+            }
+
+            if i + 1 < steps.len() && !self.dry_run {
+                output::PrintLine::info(&format!(
+                    "Waiting {} seconds before the next step",
+                    step_wait.as_secs()
+                ));
+                deadline
+                    .run(async {
+                        sleep(step_wait).await;
+                        Ok(())
                     })
-                    .nth(0)
-            });
-
-            if let Some(primary) = maybe_primary {
-                if let (Some(desired_count), Some(running_count)) =
-                    (primary.desired_count, primary.running_count)
-                {
-                    if desired_count == running_count {
-                        output::PrintLine::info(&format!(
-                            "New tasks are now running. (desired_count:{}, running_count:{}",
-                            desired_count, running_count
-                        ));
-                        break;
-                    } else {
-                        output::PrintLine::info(&format!(
-                            "Waiting for new tasks to run... (desired_count:{}, running_count:{})",
-                            desired_count, running_count
-                        ));
-                    }
+                    .await?;
+                let task_set = self.describe_task_set(task_set_id).await?;
+                if !Self::is_task_set_stable(&task_set) {
+                    return Err(Box::new(CommandError::DeploymentFailed(format!(
+                        "The task set {} got unstable at {}%",
+                        task_set_id, step
+                    ))));
                 }
             }
-            sleep(Duration::from_millis(2000));
         }
-
         Ok(())
     }
+
+    async fn poll_task_set(&self, task_set_id: &str) -> Result<(), Box<dyn error::Error>> {
+        loop {
+            let task_set = self.describe_task_set(task_set_id).await?;
+            let (desired_count, running_count) = (
+                task_set.computed_desired_count.unwrap_or(0),
+                task_set.running_count.unwrap_or(0),
+            );
+            if Self::is_task_set_stable(&task_set) {
+                output::PrintLine::info(&format!(
+                    "The task set is stable. (desired_count:{}, running_count:{})",
+                    desired_count, running_count
+                ));
+                return Ok(());
+            }
+            output::PrintLine::info(&format!(
+                "Waiting for the task set to be stable... (desired_count:{}, running_count:{})",
+                desired_count, running_count
+            ));
+            sleep(self.options.poll_interval).await;
+        }
+    }
+
+    async fn describe_task_set(
+        &self,
+        task_set_id: &str,
+    ) -> Result<rusoto_ecs::TaskSet, Box<dyn error::Error>> {
+        trace!("command::service::deploy::Executer::describe_task_set");
+
+        let cluster = &self.config.cluster;
+        let service_conf = &self.config.service;
+
+        let service = self.describe_service(cluster, service_conf).await?.ok_or(
+            CommandError::ServiceNotFound {
+                cluster: cluster.to_owned(),
+                name: service_conf.name.to_owned(),
+            },
+        )?;
+        let task_set = service
+            .task_sets
+            .unwrap_or_default()
+            .into_iter()
+            .find(|task_set| task_set.id.as_deref() == Some(task_set_id))
+            .ok_or(CommandError::DeploymentFailed(format!(
+                "The task set {} no longer exists",
+                task_set_id
+            )))?;
+        Ok(task_set)
+    }
+
+    // The stability of a task set also takes the health checks of its load balancers into account.
+    fn is_task_set_stable(task_set: &rusoto_ecs::TaskSet) -> bool {
+        task_set.stability_status.as_deref() == Some("STEADY_STATE")
+            && task_set.running_count == task_set.computed_desired_count
+    }
+
+    // Errors while rolling back are reported but not returned, as in `rollback`.
+    async fn roll_back_task_sets(&self, failed_task_set_id: &str, previous_task_sets: &[String]) {
+        trace!("command::service::deploy::Executer::roll_back_task_sets");
+
+        if previous_task_sets.is_empty() {
+            output::PrintLine::error("No previous task set to roll back to");
+            return;
+        }
+
+        output::PrintLine::info(
+            "The deployment failed. Scaling the previous task sets back to 100%",
+        );
+        let res = self
+            .restore_task_sets(failed_task_set_id, previous_task_sets)
+            .await;
+
+        match res.as_ref() {
+            Ok(_) => {
+                output::PrintLine::success("Rolled back the service to the previous task sets")
+            }
+            Err(err) => {
+                output::PrintLine::error(&format!("Failed rolling back the service: {}", err))
+            }
+        }
+        output::PrintResult::emit(&json!({
+            "command": "service deploy",
+            "name": self.config.name,
+            "rollback": {
+                "task_set_ids": previous_task_sets,
+                "succeeded": res.is_ok(),
+            },
+        }));
+    }
+
+    async fn restore_task_sets(
+        &self,
+        failed_task_set_id: &str,
+        previous_task_sets: &[String],
+    ) -> Result<(), Box<dyn error::Error>> {
+        trace!("command::service::deploy::Executer::restore_task_sets");
+
+        let cluster = &self.config.cluster;
+        let service_name = &self.config.service.name;
+
+        for id in previous_task_sets.iter() {
+            self.update_task_set_scale(cluster, service_name, id, 100.0)
+                .await?;
+        }
+        self.delete_task_set(cluster, service_name, failed_task_set_id)
+            .await
+    }
+
+    fn is_deployment_failure(err: &(dyn error::Error + 'static)) -> bool {
+        matches!(
+            CommandError::find(err),
+            Some(CommandError::Timeout(_)) | Some(CommandError::DeploymentFailed(_))
+        )
+    }
+
+    // Errors while rolling back are reported but not returned,
+    // so that the failure of the deployment itself is what the command fails with.
+    async fn rollback(
+        &self,
+        previous_task_definition_arn: Option<&String>,
+        failed_task_definition_arn: &str,
+    ) {
+        trace!("command::service::deploy::Executer::rollback");
+
+        let previous_task_definition_arn = match previous_task_definition_arn {
+            Some(arn) if arn != failed_task_definition_arn => arn,
+            _ => {
+                output::PrintLine::error("No previous task definition to roll back to");
+                return;
+            }
+        };
+
+        output::PrintLine::info(&format!(
+            "The deployment failed. Rolling back the service to {}",
+            previous_task_definition_arn
+        ));
+        let res = self
+            .switch_task_definition(previous_task_definition_arn, false)
+            .await;
+
+        match res.as_ref() {
+            Ok(_) => output::PrintLine::success(&format!(
+                "Rolled back the service to {}",
+                previous_task_definition_arn
+            )),
+            Err(err) => {
+                output::PrintLine::error(&format!("Failed rolling back the service: {}", err))
+            }
+        }
+        output::PrintResult::emit(&json!({
+            "command": "service deploy",
+            "name": self.config.name,
+            "rollback": {
+                "task_definition_arn": previous_task_definition_arn,
+                "succeeded": res.is_ok(),
+            },
+        }));
+    }
 }
 
 impl<'c> EcsExecuter for Executer<'c> {
@@ -144,3 +863,35 @@ impl<'c> EcsExecuter for Executer<'c> {
         &self.ecs_client
     }
 }
+
+impl<'c> EcsJsonExecuter for Executer<'c> {
+    fn ecs_json_client(&self) -> &JsonClient {
+        &self.ecs_json_client
+    }
+}
+
+impl<'c> CodeDeployExecuter for Executer<'c> {
+    fn codedeploy_client(&self) -> &CodeDeployClient {
+        &self.codedeploy_client
+    }
+}
+
+impl<'c> MutationExecuter for Executer<'c> {
+    fn dry_run(&self) -> bool {
+        self.dry_run
+    }
+}
+
+impl<'c> ServiceExecuter for Executer<'c> {
+    fn config(&self) -> &config::command::ServiceConfig {
+        self.config
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.options.timeout
+    }
+
+    fn poll_interval(&self) -> Duration {
+        self.options.poll_interval
+    }
+}
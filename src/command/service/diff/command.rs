@@ -0,0 +1,58 @@
+use std::error;
+
+use clap;
+
+use crate::command::aws::ClientFactory;
+use crate::command::error::CommandError;
+use crate::command::selector::Selector;
+use crate::config;
+
+use super::executer::Executer;
+
+pub struct Command<'c> {
+    config: &'c config::command::Config,
+    selector: Selector<'c>,
+}
+
+impl<'c> Command<'c> {
+    pub fn from_args(config: &'c config::command::Config, args: &'c clap::ArgMatches<'c>) -> Self {
+        trace!("command::service::diff::Command::from_args");
+
+        Command {
+            config,
+            selector: Selector::from_args(args),
+        }
+    }
+
+    pub fn new(config: &'c config::command::Config, name: Option<&'c str>, all: bool) -> Self {
+        trace!("command::service::diff::Command::new");
+
+        Command {
+            config,
+            selector: Selector::new(name, all),
+        }
+    }
+
+    pub async fn run(&self) -> Result<(), Box<dyn error::Error>> {
+        trace!("command::service::diff::Command::run");
+
+        let mut drifted = false;
+
+        let service_configs =
+            self.selector
+                .select(self.config, "service", self.config.service.as_ref())?;
+        for service_config in service_configs {
+            let aws_config = self.config.aws_config(service_config.aws.as_ref());
+            let client_factory = ClientFactory::from_config(&aws_config, self.config.dry_run)?;
+            let ecs_diff_cmd = Executer::from_config(service_config, &client_factory);
+            if ecs_diff_cmd.run().await? {
+                drifted = true;
+            }
+        }
+
+        if drifted {
+            return Err(Box::new(CommandError::DriftDetected));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,151 @@
+use std::error;
+
+use rusoto_ecs::EcsClient;
+use serde_json;
+
+use crate::command::aws::{ClientFactory, JsonClient};
+use crate::command::ecs;
+use crate::command::ecs::Executer as EcsExecuter;
+use crate::command::ecs::JsonExecuter as EcsJsonExecuter;
+use crate::command::mutation::Executer as MutationExecuter;
+use crate::config;
+use crate::output;
+
+pub struct Executer<'c> {
+    ecs_client: EcsClient,
+    ecs_json_client: JsonClient,
+    dry_run: bool,
+    config: &'c config::command::ServiceConfig,
+}
+
+impl<'c> Executer<'c> {
+    pub fn from_config(
+        config: &'c config::command::ServiceConfig,
+        client_factory: &ClientFactory,
+    ) -> Self {
+        trace!("command::service::diff::Executer::from_config");
+
+        Executer {
+            ecs_client: client_factory.ecs(),
+            ecs_json_client: client_factory.ecs_json(),
+            dry_run: client_factory.dry_run(),
+            config,
+        }
+    }
+
+    /// Prints the differences, and returns whether there is any difference.
+    pub async fn run(&self) -> Result<bool, Box<dyn error::Error>> {
+        trace!("command::service::diff::Executer::run");
+
+        let service_conf = &self.config.service;
+        let cluster = &self.config.cluster;
+
+        let maybe_service = self.describe_service(cluster, service_conf).await?;
+        // Described in JSON, so that `runtimePlatform` is compared without another request.
+        let (maybe_task_definition, runtime_platform) = match self
+            .describe_task_definition_json(&service_conf.task_definition.family)
+            .await?
+        {
+            Some((task_definition, runtime_platform)) => (Some(task_definition), runtime_platform),
+            None => (None, None),
+        };
+
+        let service_changes = ecs::service_changes(service_conf, maybe_service.as_ref());
+        let recreation_changes = ecs::recreation_changes(service_conf, maybe_service.as_ref());
+        let mut task_definition_changes = ecs::task_definition_changes(
+            &service_conf.task_definition,
+            maybe_task_definition.as_ref(),
+        );
+        if maybe_task_definition.is_some() {
+            task_definition_changes.extend(ecs::runtime_platform_change(
+                &service_conf.task_definition,
+                runtime_platform.as_ref(),
+            ));
+        }
+
+        output::PrintLine::info(&format!(
+            "Service: {} (cluster: {})",
+            service_conf.name, cluster
+        ));
+        if maybe_service.is_none() {
+            output::PrintLine::info("  Service has not been exist. It will be created.");
+        }
+        Self::print_changes(&service_changes);
+        if !recreation_changes.is_empty() {
+            output::PrintLine::warn("  These changes require recreating the service:");
+            Self::print_changes(&recreation_changes);
+        }
+
+        match maybe_task_definition.as_ref() {
+            Some(task_definition) => output::PrintLine::info(&format!(
+                "Task definition: {}:{}",
+                service_conf.task_definition.family,
+                task_definition.revision.unwrap_or(0)
+            )),
+            None => output::PrintLine::info(&format!(
+                "Task definition: {} (not registered yet)",
+                service_conf.task_definition.family
+            )),
+        }
+        Self::print_changes(&task_definition_changes);
+
+        let drifted = maybe_service.is_none()
+            || !service_changes.is_empty()
+            || !recreation_changes.is_empty()
+            || !task_definition_changes.is_empty();
+        if !drifted {
+            output::PrintLine::success("No changes");
+        }
+        output::PrintResult::emit(&json!({
+            "command": "service diff",
+            "name": self.config.name,
+            "cluster": cluster,
+            "service_exists": maybe_service.is_some(),
+            "service_changes": service_changes,
+            "recreation_changes": recreation_changes,
+            "task_definition_revision": maybe_task_definition.as_ref().and_then(|td| td.revision),
+            "task_definition_changes": task_definition_changes,
+            "drifted": drifted,
+        }));
+
+        Ok(drifted)
+    }
+
+    fn print_changes(changes: &[ecs::Change]) {
+        for change in changes {
+            let current = change
+                .current
+                .as_ref()
+                .map(|v| serde_json::to_string(v).unwrap_or_default());
+            let desired = change
+                .desired
+                .as_ref()
+                .map(|v| serde_json::to_string(v).unwrap_or_default());
+
+            match (current, desired) {
+                (Some(c), Some(d)) => output::PrintDiff::changed(&change.path, &c, &d),
+                (None, Some(d)) => output::PrintDiff::added(&change.path, &d),
+                (Some(c), None) => output::PrintDiff::removed(&change.path, &c),
+                (None, None) => {}
+            }
+        }
+    }
+}
+
+impl<'c> EcsExecuter for Executer<'c> {
+    fn ecs_client(&self) -> &EcsClient {
+        &self.ecs_client
+    }
+}
+
+impl<'c> EcsJsonExecuter for Executer<'c> {
+    fn ecs_json_client(&self) -> &JsonClient {
+        &self.ecs_json_client
+    }
+}
+
+impl<'c> MutationExecuter for Executer<'c> {
+    fn dry_run(&self) -> bool {
+        self.dry_run
+    }
+}
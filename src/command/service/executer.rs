@@ -0,0 +1,116 @@
+use async_trait::async_trait;
+use std::error;
+use std::time::Duration;
+
+use rusoto_ecs;
+use tokio::time::sleep;
+
+use crate::command::ecs::Executer as EcsExecuter;
+use crate::command::error::CommandError;
+use crate::command::wait;
+use crate::config;
+use crate::output;
+
+#[async_trait]
+pub trait Executer: EcsExecuter {
+    fn config(&self) -> &config::command::ServiceConfig;
+
+    fn timeout(&self) -> Option<Duration>;
+
+    fn poll_interval(&self) -> Duration;
+
+    /// Updates the service only with the given task definition, keeping the other settings.
+    async fn switch_task_definition(
+        &self,
+        task_definition_arn: &str,
+        no_wait: bool,
+    ) -> Result<(), Box<dyn error::Error>> {
+        trace!("command::service::Executer::switch_task_definition");
+
+        self.update_service_task_definition(
+            &self.config().cluster,
+            &self.config().service.name,
+            task_definition_arn,
+        )
+        .await?;
+
+        if !no_wait && !self.dry_run() {
+            self.wait_for_green(task_definition_arn).await?;
+        }
+        Ok(())
+    }
+
+    async fn wait_for_green(&self, task_definition_arn: &str) -> Result<(), Box<dyn error::Error>> {
+        trace!("command::service::Executer::wait_for_green");
+
+        wait::with_timeout(self.timeout(), self.poll_for_green(task_definition_arn)).await
+    }
+
+    async fn poll_for_green(&self, task_definition_arn: &str) -> Result<(), Box<dyn error::Error>> {
+        let cluster = &self.config().cluster;
+        let service_conf = &self.config().service;
+
+        loop {
+            let maybe_service = self.describe_service(cluster, service_conf).await?;
+            let service = maybe_service.ok_or(CommandError::ServiceNotFound {
+                cluster: cluster.to_owned(),
+                name: service_conf.name.to_owned(),
+            })?;
+
+            // When the deployment circuit breaker rolls back by itself,
+            // the deployment of the task definition is no longer the primary one.
+            let maybe_deployment = service.deployments.as_ref().and_then(|deployments| {
+                let mut deployments = deployments
+                    .iter()
+                    .filter(|deployment| {
+                        deployment.task_definition.as_deref() == Some(task_definition_arn)
+                    })
+                    .collect::<Vec<&rusoto_ecs::Deployment>>();
+                deployments
+                    .sort_by_key(|deployment| deployment.status.as_deref() != Some("PRIMARY"));
+                deployments.first().cloned()
+            });
+
+            if let Some(deployment) = maybe_deployment {
+                if deployment.rollout_state.as_deref() == Some("FAILED") {
+                    let reason = deployment
+                        .rollout_state_reason
+                        .to_owned()
+                        .unwrap_or_else(|| String::from("The rollout failed"));
+                    return Err(Box::new(CommandError::DeploymentFailed(reason)));
+                }
+
+                if let (Some(failed_tasks), Some(max_failed_tasks)) =
+                    (deployment.failed_tasks, self.config().max_failed_tasks)
+                {
+                    if failed_tasks > max_failed_tasks {
+                        return Err(Box::new(CommandError::DeploymentFailed(format!(
+                            "{} tasks failed to start",
+                            failed_tasks
+                        ))));
+                    }
+                }
+
+                if let (Some(desired_count), Some(running_count)) =
+                    (deployment.desired_count, deployment.running_count)
+                {
+                    if desired_count == running_count {
+                        output::PrintLine::info(&format!(
+                            "New tasks are now running. (desired_count:{}, running_count:{}",
+                            desired_count, running_count
+                        ));
+                        break;
+                    } else {
+                        output::PrintLine::info(&format!(
+                            "Waiting for new tasks to run... (desired_count:{}, running_count:{})",
+                            desired_count, running_count
+                        ));
+                    }
+                }
+            }
+            sleep(self.poll_interval()).await;
+        }
+
+        Ok(())
+    }
+}
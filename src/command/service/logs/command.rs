@@ -0,0 +1,125 @@
+use std::error;
+use std::time::Duration;
+
+use clap;
+
+use crate::command::aws::ClientFactory;
+use crate::command::error::CommandError;
+use crate::command::wait;
+use crate::config;
+
+use super::executer::{Executer, ExecuterOptions};
+
+const DEFAULT_SINCE: Duration = Duration::from_secs(10 * 60);
+
+/// Parses a duration like `30s`, `10m`, `2h` or `1d`. A bare number is taken as seconds.
+pub fn parse_since(since_str: &str) -> Option<Duration> {
+    let (number, unit) = match since_str.find(|c: char| !c.is_ascii_digit()) {
+        Some(index) => since_str.split_at(index),
+        None => (since_str, "s"),
+    };
+    let number: u64 = number.parse().ok()?;
+    let seconds = match unit {
+        "s" => number,
+        "m" => number * 60,
+        "h" => number * 60 * 60,
+        "d" => number * 24 * 60 * 60,
+        _ => return None,
+    };
+    Some(Duration::from_secs(seconds))
+}
+
+pub struct Command<'c> {
+    config: &'c config::command::Config,
+    name: &'c str,
+    follow: bool,
+    since: Duration,
+    filter: Option<&'c str>,
+    container: Option<&'c str>,
+    poll_interval: Option<u64>,
+}
+
+impl<'c> Command<'c> {
+    pub fn from_args(config: &'c config::command::Config, args: &'c clap::ArgMatches<'c>) -> Self {
+        trace!("command::service::logs::Command::from_args");
+
+        Command {
+            config,
+            name: args.value_of("NAME").unwrap(),
+            follow: args.is_present("FOLLOW"),
+            since: args
+                .value_of("SINCE")
+                .and_then(parse_since)
+                .unwrap_or(DEFAULT_SINCE),
+            filter: args.value_of("FILTER"),
+            container: args.value_of("CONTAINER"),
+            poll_interval: args.value_of("POLL_INTERVAL").and_then(|v| v.parse().ok()),
+        }
+    }
+
+    pub fn new(config: &'c config::command::Config, name: &'c str, follow: bool) -> Self {
+        trace!("command::service::logs::Command::new");
+
+        Command {
+            config,
+            name,
+            follow,
+            since: DEFAULT_SINCE,
+            filter: None,
+            container: None,
+            poll_interval: None,
+        }
+    }
+
+    pub async fn run(&self) -> Result<(), Box<dyn error::Error>> {
+        trace!("command::service::logs::Command::run");
+
+        if !self
+            .config
+            .service
+            .iter()
+            .flatten()
+            .any(|c| c.name == self.name)
+        {
+            return Err(Box::new(CommandError::ConfigEntryNotFound {
+                section: "service",
+                name: self.name.to_owned(),
+            }));
+        }
+
+        if let Some(service_config_group) = self.config.service.as_ref() {
+            for service_config in service_config_group {
+                if service_config.name != self.name {
+                    continue;
+                }
+
+                let (_, poll_interval) =
+                    wait::durations(None, self.poll_interval, None, service_config.poll_interval);
+                let options = ExecuterOptions {
+                    follow: self.follow,
+                    since: self.since,
+                    filter: self.filter.map(|f| f.to_owned()),
+                    container: self.container.map(|c| c.to_owned()),
+                    poll_interval,
+                };
+                let aws_config = self.config.aws_config(service_config.aws.as_ref());
+                let client_factory = ClientFactory::from_config(&aws_config, self.config.dry_run)?;
+                let ecs_logs_cmd = Executer::from_config(service_config, &client_factory, &options);
+                ecs_logs_cmd.run().await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_parse_since() {
+    assert_eq!(parse_since("90"), Some(Duration::from_secs(90)));
+    assert_eq!(parse_since("30s"), Some(Duration::from_secs(30)));
+    assert_eq!(parse_since("10m"), Some(Duration::from_secs(600)));
+    assert_eq!(parse_since("2h"), Some(Duration::from_secs(7200)));
+    assert_eq!(parse_since("1d"), Some(Duration::from_secs(86400)));
+    assert_eq!(parse_since("1w"), None);
+    assert_eq!(parse_since("m"), None);
+}
@@ -0,0 +1,230 @@
+use std::collections::{HashMap, HashSet};
+use std::error;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tokio::time::sleep;
+
+use rusoto_ecs;
+use rusoto_ecs::EcsClient;
+use rusoto_logs;
+use rusoto_logs::CloudWatchLogsClient;
+
+use crate::command::aws::ClientFactory;
+use crate::command::cloudwatch_logs::Executer as CloudWatchLogsExecuter;
+use crate::command::cloudwatch_logs::LogStream;
+use crate::command::ecs::Executer as EcsExecuter;
+use crate::command::mutation::Executer as MutationExecuter;
+use crate::config;
+use crate::output;
+
+pub struct ExecuterOptions {
+    pub follow: bool,
+    pub since: Duration,
+    pub filter: Option<String>,
+    pub container: Option<String>,
+    pub poll_interval: Duration,
+}
+
+pub struct Executer<'c> {
+    ecs_client: EcsClient,
+    logs_client: CloudWatchLogsClient,
+    dry_run: bool,
+    config: &'c config::command::ServiceConfig,
+    options: &'c ExecuterOptions,
+}
+
+impl<'c> Executer<'c> {
+    pub fn from_config(
+        config: &'c config::command::ServiceConfig,
+        client_factory: &ClientFactory,
+        options: &'c ExecuterOptions,
+    ) -> Self {
+        trace!("command::service::logs::Executer::from_config");
+
+        Executer {
+            ecs_client: client_factory.ecs(),
+            logs_client: client_factory.logs(),
+            dry_run: client_factory.dry_run(),
+            config,
+            options,
+        }
+    }
+
+    pub async fn run(&self) -> Result<(), Box<dyn error::Error>> {
+        trace!("command::service::logs::Executer::run");
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+        let mut start_time = now.saturating_sub(self.options.since).as_millis() as i64;
+        // Events at the start time are listed again in the next round.
+        let mut printed_ids = HashSet::new();
+        let mut task_definitions = HashMap::new();
+
+        loop {
+            // Tasks are listed in every round, since they are replaced by deployments and scaling.
+            let streams = self.log_streams(&mut task_definitions).await?;
+            if streams.is_empty() && !self.options.follow {
+                output::PrintLine::info("No logs of running tasks found");
+                return Ok(());
+            }
+
+            let events = self.log_events(&streams, start_time).await?;
+            for (stream, event) in events.iter() {
+                if let Some(event_id) = event.event_id.as_ref() {
+                    if printed_ids.contains(event_id) {
+                        continue;
+                    }
+                }
+                Self::print(stream, event);
+            }
+
+            if let Some(last_time) = events.iter().filter_map(|(_, e)| e.timestamp).max() {
+                if last_time > start_time {
+                    printed_ids.clear();
+                    start_time = last_time;
+                }
+                printed_ids.extend(
+                    events
+                        .iter()
+                        .filter(|(_, e)| e.timestamp == Some(last_time))
+                        .filter_map(|(_, e)| e.event_id.to_owned()),
+                );
+            }
+
+            if !self.options.follow {
+                return Ok(());
+            }
+            sleep(self.options.poll_interval).await;
+        }
+    }
+
+    async fn log_streams(
+        &self,
+        task_definitions: &mut HashMap<String, rusoto_ecs::TaskDefinition>,
+    ) -> Result<Vec<LogStream>, Box<dyn error::Error>> {
+        trace!("command::service::logs::Executer::log_streams");
+
+        let cluster = &self.config.cluster;
+        let task_arns = self
+            .list_service_tasks(cluster, &self.config.service.name, "RUNNING")
+            .await?;
+        let tasks = self.describe_tasks(cluster, &task_arns).await?;
+
+        let mut streams = Vec::new();
+        for task in tasks.iter() {
+            let (task_arn, task_definition_arn) =
+                match (task.task_arn.as_ref(), task.task_definition_arn.as_ref()) {
+                    (Some(task_arn), Some(task_definition_arn)) => (task_arn, task_definition_arn),
+                    _ => continue,
+                };
+
+            if !task_definitions.contains_key(task_definition_arn) {
+                if let Some(task_definition) =
+                    self.describe_task_definition(task_definition_arn).await?
+                {
+                    task_definitions.insert(task_definition_arn.to_owned(), task_definition);
+                }
+            }
+
+            if let Some(task_definition) = task_definitions.get(task_definition_arn) {
+                streams.extend(
+                    LogStream::from_task(task_definition, task_arn)
+                        .into_iter()
+                        .filter(|stream| match self.options.container.as_ref() {
+                            Some(container) => &stream.container_name == container,
+                            None => true,
+                        }),
+                );
+            }
+        }
+
+        Ok(streams)
+    }
+
+    /// Lists the events of all streams, interleaved by the timestamp.
+    async fn log_events(
+        &self,
+        streams: &[LogStream],
+        start_time: i64,
+    ) -> Result<Vec<(LogStream, rusoto_logs::FilteredLogEvent)>, Box<dyn error::Error>> {
+        trace!("command::service::logs::Executer::log_events");
+
+        let mut streams_by_group: HashMap<&str, Vec<&LogStream>> = HashMap::new();
+        for stream in streams.iter() {
+            streams_by_group
+                .entry(stream.log_group.as_str())
+                .or_default()
+                .push(stream);
+        }
+
+        let mut events = Vec::new();
+        for (log_group, group_streams) in streams_by_group.iter() {
+            let stream_names: Vec<String> = group_streams
+                .iter()
+                .map(|stream| stream.log_stream.to_owned())
+                .collect();
+            let group_events = self
+                .filter_log_events(
+                    log_group,
+                    &stream_names,
+                    start_time,
+                    self.options.filter.as_deref(),
+                )
+                .await?;
+
+            for event in group_events {
+                let stream = group_streams
+                    .iter()
+                    .find(|stream| event.log_stream_name.as_ref() == Some(&stream.log_stream));
+                if let Some(stream) = stream {
+                    events.push(((*stream).to_owned(), event));
+                }
+            }
+        }
+
+        events.sort_by_key(|(_, event)| event.timestamp);
+        Ok(events)
+    }
+
+    fn print(stream: &LogStream, event: &rusoto_logs::FilteredLogEvent) {
+        let message = event.message.as_deref().unwrap_or("");
+
+        if output::format() == output::Format::Json {
+            output::PrintResult::emit(&json!({
+                "timestamp": event.timestamp,
+                "task_id": stream.task_id,
+                "container_name": stream.container_name,
+                "message": message,
+            }));
+            return;
+        }
+
+        output::PrintLine::print(&format!(
+            "{} [{}/{}] {}",
+            event
+                .timestamp
+                .map(|t| output::format_timestamp(t as f64 / 1000.0))
+                .unwrap_or_default(),
+            stream.task_id,
+            stream.container_name,
+            message.trim_end()
+        ));
+    }
+}
+
+impl<'c> CloudWatchLogsExecuter for Executer<'c> {
+    fn logs_client(&self) -> &CloudWatchLogsClient {
+        &self.logs_client
+    }
+}
+
+impl<'c> EcsExecuter for Executer<'c> {
+    fn ecs_client(&self) -> &EcsClient {
+        &self.ecs_client
+    }
+}
+
+impl<'c> MutationExecuter for Executer<'c> {
+    fn dry_run(&self) -> bool {
+        self.dry_run
+    }
+}
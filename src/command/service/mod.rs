@@ -1,2 +1,10 @@
 pub mod deploy;
+pub mod diff;
+pub mod logs;
+pub mod rollback;
+pub mod status;
 pub mod stop;
+
+mod executer;
+
+pub use self::executer::Executer;
@@ -0,0 +1,97 @@
+use std::error;
+
+use clap;
+
+use crate::command::aws::ClientFactory;
+use crate::command::error::CommandError;
+use crate::command::wait;
+use crate::config;
+
+use super::executer::{Executer, ExecuterOptions};
+
+pub struct Command<'c> {
+    config: &'c config::command::Config,
+    name: &'c str,
+    to: Option<i64>,
+    no_wait: bool,
+    timeout: Option<u64>,
+    poll_interval: Option<u64>,
+}
+
+impl<'c> Command<'c> {
+    pub fn from_args(config: &'c config::command::Config, args: &'c clap::ArgMatches<'c>) -> Self {
+        trace!("command::service::rollback::Command::from_args");
+
+        Command {
+            config,
+            name: args.value_of("NAME").unwrap(),
+            to: args.value_of("TO").and_then(|v| v.parse().ok()),
+            no_wait: args.is_present("NO_WAIT"),
+            timeout: args.value_of("TIMEOUT").and_then(|v| v.parse().ok()),
+            poll_interval: args.value_of("POLL_INTERVAL").and_then(|v| v.parse().ok()),
+        }
+    }
+
+    pub fn new(
+        config: &'c config::command::Config,
+        name: &'c str,
+        to: Option<i64>,
+        no_wait: bool,
+    ) -> Self {
+        trace!("command::service::rollback::Command::new");
+
+        Command {
+            config,
+            name,
+            to,
+            no_wait,
+            timeout: None,
+            poll_interval: None,
+        }
+    }
+
+    pub async fn run(&self) -> Result<(), Box<dyn error::Error>> {
+        trace!("command::service::rollback::Command::run");
+
+        if !self
+            .config
+            .service
+            .iter()
+            .flatten()
+            .any(|c| c.name == self.name)
+        {
+            return Err(Box::new(CommandError::ConfigEntryNotFound {
+                section: "service",
+                name: self.name.to_owned(),
+            }));
+        }
+
+        if let Some(service_config_group) = self.config.service.as_ref() {
+            for service_config in service_config_group {
+                if service_config.name != self.name {
+                    continue;
+                }
+
+                let (timeout, poll_interval) = wait::durations(
+                    self.timeout,
+                    self.poll_interval,
+                    service_config.timeout,
+                    service_config.poll_interval,
+                );
+                let options = ExecuterOptions {
+                    to: self.to,
+                    no_wait: self.no_wait,
+                    timeout,
+                    poll_interval,
+                };
+                let aws_config = self.config.aws_config(service_config.aws.as_ref());
+                let client_factory = ClientFactory::from_config(&aws_config, self.config.dry_run)?;
+                let ecs_rollback_cmd =
+                    Executer::from_config(service_config, &client_factory, &options);
+                ecs_rollback_cmd.run().await?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,200 @@
+use std::error;
+use std::io::Write;
+use std::time::Duration;
+use tabwriter::TabWriter;
+
+use rusoto_ecs;
+use rusoto_ecs::EcsClient;
+
+use crate::command::aws::ClientFactory;
+use crate::command::ecs;
+use crate::command::ecs::Executer as EcsExecuter;
+use crate::command::error::CommandError;
+use crate::command::mutation::Executer as MutationExecuter;
+use crate::command::service::Executer as ServiceExecuter;
+use crate::config;
+use crate::output;
+
+const LISTED_REVISIONS: usize = 10;
+
+pub struct ExecuterOptions {
+    pub to: Option<i64>,
+    pub no_wait: bool,
+    pub timeout: Option<Duration>,
+    pub poll_interval: Duration,
+}
+
+pub struct Executer<'c> {
+    ecs_client: EcsClient,
+    dry_run: bool,
+    config: &'c config::command::ServiceConfig,
+    options: &'c ExecuterOptions,
+}
+
+impl<'c> Executer<'c> {
+    pub fn from_config(
+        config: &'c config::command::ServiceConfig,
+        client_factory: &ClientFactory,
+        options: &'c ExecuterOptions,
+    ) -> Self {
+        trace!("command::service::rollback::Executer::from_config");
+
+        Executer {
+            ecs_client: client_factory.ecs(),
+            dry_run: client_factory.dry_run(),
+            config,
+            options,
+        }
+    }
+
+    pub async fn run(&self) -> Result<(), Box<dyn error::Error>> {
+        trace!("command::service::rollback::Executer::run");
+
+        let service_conf = &self.config.service;
+        let cluster = &self.config.cluster;
+        let family = &service_conf.task_definition.family;
+
+        let maybe_service = self.describe_service(cluster, service_conf).await?;
+        let service = maybe_service.ok_or(CommandError::ServiceNotFound {
+            cluster: cluster.to_owned(),
+            name: service_conf.name.to_owned(),
+        })?;
+        let current_arn =
+            service
+                .task_definition
+                .as_ref()
+                .ok_or(CommandError::UnexpectedResponse {
+                    operation: "DescribeServices",
+                    detail: "no task definition of the service",
+                })?;
+        let current_revision = ecs::revision_of(current_arn);
+
+        let arns = self
+            .list_task_definition_arns(family, LISTED_REVISIONS)
+            .await?;
+        let mut task_definitions = Vec::new();
+        for arn in arns.iter() {
+            if let Some(task_definition) = self.describe_task_definition(arn).await? {
+                task_definitions.push(task_definition);
+            }
+        }
+        self.print(&task_definitions, current_arn)?;
+
+        let target_arn = match self.options.to {
+            Some(revision) => {
+                let task_definition = self
+                    .describe_task_definition(&format!("{}:{}", family, revision))
+                    .await?;
+                task_definition
+                    .and_then(|td| td.task_definition_arn)
+                    .ok_or(CommandError::TaskDefinitionNotFound(format!(
+                        "{}:{}",
+                        family, revision
+                    )))?
+            }
+            None => {
+                let previous = arns
+                    .iter()
+                    .find(|arn| ecs::revision_of(arn) < current_revision)
+                    .cloned();
+                previous.ok_or(CommandError::TaskDefinitionNotFound(format!(
+                    "{} (a revision before {})",
+                    family, current_revision
+                )))?
+            }
+        };
+
+        if &target_arn == current_arn {
+            output::PrintLine::info(&format!(
+                "The service is already running the revision: {}",
+                current_revision
+            ));
+            return Ok(());
+        }
+
+        output::PrintLine::info(&format!(
+            "Rolling back the service to the revision: {}",
+            ecs::revision_of(&target_arn)
+        ));
+        self.switch_task_definition(&target_arn, self.options.no_wait)
+            .await?;
+
+        output::PrintLine::success("Rollback completed");
+        output::PrintResult::emit(&json!({
+            "command": "service rollback",
+            "name": self.config.name,
+            "cluster": cluster,
+            "service_arn": service.service_arn,
+            "previous_task_definition_arn": current_arn,
+            "task_definition_arn": target_arn,
+            "revision": ecs::revision_of(&target_arn),
+        }));
+        Ok(())
+    }
+
+    fn print(
+        &self,
+        task_definitions: &[rusoto_ecs::TaskDefinition],
+        current_arn: &str,
+    ) -> Result<(), Box<dyn error::Error>> {
+        let mut tw = TabWriter::new(output::text_writer());
+
+        writeln!(&mut tw, "\tREVISION\tREGISTERED AT\tIMAGES")?;
+        for td in task_definitions.iter() {
+            let current = td.task_definition_arn.as_deref() == Some(current_arn);
+            let registered_at = td
+                .registered_at
+                .map(output::format_timestamp)
+                .unwrap_or_default();
+            let images = td
+                .container_definitions
+                .as_ref()
+                .map(|cds| {
+                    cds.iter()
+                        .filter_map(|cd| cd.image.as_ref())
+                        .map(|image| image.rsplit('/').next().unwrap_or(image))
+                        .collect::<Vec<&str>>()
+                        .join(", ")
+                })
+                .unwrap_or_default();
+
+            writeln!(
+                &mut tw,
+                "{}\t{}\t{}\t{}",
+                if current { "*" } else { "" },
+                td.revision.unwrap_or(0),
+                registered_at,
+                images
+            )?;
+        }
+
+        tw.flush()?;
+        Ok(())
+    }
+}
+
+impl<'c> EcsExecuter for Executer<'c> {
+    fn ecs_client(&self) -> &EcsClient {
+        &self.ecs_client
+    }
+}
+
+impl<'c> MutationExecuter for Executer<'c> {
+    fn dry_run(&self) -> bool {
+        self.dry_run
+    }
+}
+
+impl<'c> ServiceExecuter for Executer<'c> {
+    fn config(&self) -> &config::command::ServiceConfig {
+        self.config
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.options.timeout
+    }
+
+    fn poll_interval(&self) -> Duration {
+        self.options.poll_interval
+    }
+}
@@ -0,0 +1,61 @@
+use std::error;
+
+use clap;
+
+use crate::command::aws::ClientFactory;
+use crate::command::selector::Selector;
+use crate::config;
+
+use super::executer::{Executer, ExecuterOptions};
+
+const DEFAULT_EVENTS: usize = 10;
+
+pub struct Command<'c> {
+    config: &'c config::command::Config,
+    selector: Selector<'c>,
+    events: usize,
+}
+
+impl<'c> Command<'c> {
+    pub fn from_args(config: &'c config::command::Config, args: &'c clap::ArgMatches<'c>) -> Self {
+        trace!("command::service::status::Command::from_args");
+
+        Command {
+            config,
+            selector: Selector::from_args(args),
+            events: args
+                .value_of("EVENTS")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_EVENTS),
+        }
+    }
+
+    pub fn new(config: &'c config::command::Config, name: Option<&'c str>, all: bool) -> Self {
+        trace!("command::service::status::Command::new");
+
+        Command {
+            config,
+            selector: Selector::new(name, all),
+            events: DEFAULT_EVENTS,
+        }
+    }
+
+    pub async fn run(&self) -> Result<(), Box<dyn error::Error>> {
+        trace!("command::service::status::Command::run");
+
+        let service_configs =
+            self.selector
+                .select(self.config, "service", self.config.service.as_ref())?;
+        for service_config in service_configs {
+            let options = ExecuterOptions {
+                events: self.events,
+            };
+            let aws_config = self.config.aws_config(service_config.aws.as_ref());
+            let client_factory = ClientFactory::from_config(&aws_config, self.config.dry_run)?;
+            let ecs_status_cmd = Executer::from_config(service_config, &client_factory, &options);
+            ecs_status_cmd.run().await?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,262 @@
+use std::error;
+use std::io::Write;
+use tabwriter::TabWriter;
+
+use rusoto_ecs;
+use rusoto_ecs::EcsClient;
+use serde_json;
+
+use crate::command::aws::ClientFactory;
+use crate::command::ecs;
+use crate::command::ecs::Executer as EcsExecuter;
+use crate::command::mutation::Executer as MutationExecuter;
+use crate::config;
+use crate::output;
+
+pub struct ExecuterOptions {
+    pub events: usize,
+}
+
+pub struct Executer<'c> {
+    ecs_client: EcsClient,
+    dry_run: bool,
+    config: &'c config::command::ServiceConfig,
+    options: &'c ExecuterOptions,
+}
+
+impl<'c> Executer<'c> {
+    pub fn from_config(
+        config: &'c config::command::ServiceConfig,
+        client_factory: &ClientFactory,
+        options: &'c ExecuterOptions,
+    ) -> Self {
+        trace!("command::service::status::Executer::from_config");
+
+        Executer {
+            ecs_client: client_factory.ecs(),
+            dry_run: client_factory.dry_run(),
+            config,
+            options,
+        }
+    }
+
+    pub async fn run(&self) -> Result<(), Box<dyn error::Error>> {
+        trace!("command::service::status::Executer::run");
+
+        let service_conf = &self.config.service;
+        let cluster = &self.config.cluster;
+
+        output::PrintLine::info(&format!(
+            "Service: {} (cluster: {})",
+            service_conf.name, cluster
+        ));
+
+        let service = match self.describe_service(cluster, service_conf).await? {
+            Some(s) => s,
+            None => {
+                output::PrintLine::info("  Service has not been exist.");
+                return Ok(());
+            }
+        };
+
+        // Stopped tasks are kept for a while, so that the reasons of failures can be seen.
+        let mut task_arns = self
+            .list_service_tasks(cluster, &service_conf.name, "RUNNING")
+            .await?;
+        task_arns.extend(
+            self.list_service_tasks(cluster, &service_conf.name, "STOPPED")
+                .await?,
+        );
+        let tasks = self.describe_tasks(cluster, &task_arns).await?;
+
+        output::PrintResult::emit(&Self::to_result(
+            &self.config.name,
+            &service,
+            &tasks,
+            self.options.events,
+        ));
+
+        output::PrintLine::info("");
+        self.print_deployments(&service)?;
+        output::PrintLine::info("");
+        self.print_tasks(&tasks)?;
+        output::PrintLine::info("");
+        self.print_events(&service)?;
+
+        Ok(())
+    }
+
+    fn to_result(
+        name: &str,
+        service: &rusoto_ecs::Service,
+        tasks: &[rusoto_ecs::Task],
+        events: usize,
+    ) -> serde_json::Value {
+        let deployments: Vec<serde_json::Value> = service
+            .deployments
+            .as_ref()
+            .unwrap_or(&vec![])
+            .iter()
+            .map(|deployment| {
+                json!({
+                    "status": deployment.status,
+                    "task_definition_arn": deployment.task_definition,
+                    "desired_count": deployment.desired_count,
+                    "pending_count": deployment.pending_count,
+                    "running_count": deployment.running_count,
+                    "failed_tasks": deployment.failed_tasks,
+                    "rollout_state": deployment.rollout_state,
+                    "rollout_state_reason": deployment.rollout_state_reason,
+                    "updated_at": deployment.updated_at,
+                })
+            })
+            .collect();
+        let tasks: Vec<serde_json::Value> = tasks
+            .iter()
+            .map(|task| {
+                json!({
+                    "task_arn": task.task_arn,
+                    "task_definition_arn": task.task_definition_arn,
+                    "last_status": task.last_status,
+                    "health_status": task.health_status,
+                    "started_at": task.started_at,
+                    "stopped_at": task.stopped_at,
+                    "stopped_reason": task.stopped_reason,
+                })
+            })
+            .collect();
+        let events: Vec<serde_json::Value> = service
+            .events
+            .as_ref()
+            .unwrap_or(&vec![])
+            .iter()
+            .take(events)
+            .map(|event| {
+                json!({
+                    "created_at": event.created_at,
+                    "message": event.message,
+                })
+            })
+            .collect();
+
+        json!({
+            "command": "service status",
+            "name": name,
+            "service_arn": service.service_arn,
+            "status": service.status,
+            "desired_count": service.desired_count,
+            "pending_count": service.pending_count,
+            "running_count": service.running_count,
+            "deployments": deployments,
+            "tasks": tasks,
+            "events": events,
+        })
+    }
+
+    fn print_deployments(
+        &self,
+        service: &rusoto_ecs::Service,
+    ) -> Result<(), Box<dyn error::Error>> {
+        let mut tw = TabWriter::new(output::text_writer());
+
+        writeln!(
+            &mut tw,
+            "DEPLOYMENT\tREVISION\tDESIRED\tPENDING\tRUNNING\tFAILED\tROLLOUT\tUPDATED AT"
+        )?;
+        for deployment in service.deployments.as_ref().unwrap_or(&vec![]).iter() {
+            writeln!(
+                &mut tw,
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                deployment.status.as_deref().unwrap_or(""),
+                deployment
+                    .task_definition
+                    .as_ref()
+                    .map(|arn| ecs::revision_of(arn))
+                    .unwrap_or(0),
+                deployment.desired_count.unwrap_or(0),
+                deployment.pending_count.unwrap_or(0),
+                deployment.running_count.unwrap_or(0),
+                deployment.failed_tasks.unwrap_or(0),
+                deployment.rollout_state.as_deref().unwrap_or(""),
+                deployment
+                    .updated_at
+                    .map(output::format_timestamp)
+                    .unwrap_or_default(),
+            )?;
+        }
+
+        tw.flush()?;
+        Ok(())
+    }
+
+    fn print_tasks(&self, tasks: &[rusoto_ecs::Task]) -> Result<(), Box<dyn error::Error>> {
+        let mut tw = TabWriter::new(output::text_writer());
+
+        writeln!(
+            &mut tw,
+            "TASK\tREVISION\tSTATUS\tHEALTH\tSTARTED AT\tSTOPPED REASON"
+        )?;
+        for task in tasks.iter() {
+            writeln!(
+                &mut tw,
+                "{}\t{}\t{}\t{}\t{}\t{}",
+                task.task_arn
+                    .as_ref()
+                    .and_then(|arn| arn.rsplit('/').next())
+                    .unwrap_or(""),
+                task.task_definition_arn
+                    .as_ref()
+                    .map(|arn| ecs::revision_of(arn))
+                    .unwrap_or(0),
+                task.last_status.as_deref().unwrap_or(""),
+                task.health_status.as_deref().unwrap_or(""),
+                task.started_at
+                    .map(output::format_timestamp)
+                    .unwrap_or_default(),
+                task.stopped_reason.as_deref().unwrap_or(""),
+            )?;
+        }
+
+        tw.flush()?;
+        Ok(())
+    }
+
+    fn print_events(&self, service: &rusoto_ecs::Service) -> Result<(), Box<dyn error::Error>> {
+        let mut tw = TabWriter::new(output::text_writer());
+
+        // Events are returned from the latest one.
+        writeln!(&mut tw, "EVENT AT\tMESSAGE")?;
+        for event in service
+            .events
+            .as_ref()
+            .unwrap_or(&vec![])
+            .iter()
+            .take(self.options.events)
+        {
+            writeln!(
+                &mut tw,
+                "{}\t{}",
+                event
+                    .created_at
+                    .map(output::format_timestamp)
+                    .unwrap_or_default(),
+                event.message.as_deref().unwrap_or(""),
+            )?;
+        }
+
+        tw.flush()?;
+        Ok(())
+    }
+}
+
+impl<'c> EcsExecuter for Executer<'c> {
+    fn ecs_client(&self) -> &EcsClient {
+        &self.ecs_client
+    }
+}
+
+impl<'c> MutationExecuter for Executer<'c> {
+    fn dry_run(&self) -> bool {
+        self.dry_run
+    }
+}
@@ -0,0 +1,5 @@
+mod command;
+mod executer;
+
+pub use self::command::Command;
+pub use self::executer::Executer;
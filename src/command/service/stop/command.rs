@@ -2,15 +2,16 @@ use std::error;
 
 use clap;
 
+use crate::command::aws::ClientFactory;
+use crate::command::selector::Selector;
 use crate::config;
 
 use super::executer::{Executer, ExecuterOptions};
 
 pub struct Command<'c> {
     config: &'c config::command::Config,
-    name: Option<&'c str>,
+    selector: Selector<'c>,
     no_wait: bool,
-    all: bool,
 }
 
 impl<'c> Command<'c> {
@@ -19,9 +20,8 @@ impl<'c> Command<'c> {
 
         Command {
             config: config,
-            name: args.value_of("NAME"),
+            selector: Selector::from_args(args),
             no_wait: args.is_present("NO_WAIT"),
-            all: args.is_present("ALL"),
         }
     }
 
@@ -35,36 +35,25 @@ impl<'c> Command<'c> {
 
         Command {
             config: config,
-            name: name,
+            selector: Selector::new(name, all),
             no_wait: no_wait,
-            all: all,
         }
     }
 
     pub async fn run(&self) -> Result<(), Box<dyn error::Error>> {
         trace!("command::service::stop::Command::run");
 
-        if let Some(service_config_group) = self.config.service.as_ref() {
-            for service_config in service_config_group {
-                let mut runnable: bool = false;
-                if let Some(name) = self.name {
-                    if name == service_config.name {
-                        runnable = true;
-                    }
-                }
-                if self.all {
-                    runnable = true;
-                }
-                if !runnable {
-                    continue;
-                }
-
-                let options = ExecuterOptions {
-                    no_wait: self.no_wait,
-                };
-                let ecs_stop_cmd = Executer::from_config(&service_config, &options);
-                ecs_stop_cmd.run().await?;
-            }
+        let service_configs =
+            self.selector
+                .select(self.config, "service", self.config.service.as_ref())?;
+        for service_config in service_configs {
+            let options = ExecuterOptions {
+                no_wait: self.no_wait,
+            };
+            let aws_config = self.config.aws_config(service_config.aws.as_ref());
+            let client_factory = ClientFactory::from_config(&aws_config, self.config.dry_run)?;
+            let ecs_stop_cmd = Executer::from_config(service_config, &client_factory, &options);
+            ecs_stop_cmd.run().await?;
         }
 
         Ok(())
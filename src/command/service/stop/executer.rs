@@ -1,10 +1,11 @@
 use std::error;
 
-use rusoto_core::Region;
 use rusoto_ecs::EcsClient;
 
+use crate::command::aws::ClientFactory;
 use crate::command::ecs::Executer as EcsExecuter;
 use crate::command::error::CommandError;
+use crate::command::mutation::Executer as MutationExecuter;
 use crate::config;
 use crate::output;
 
@@ -15,6 +16,7 @@ pub struct ExecuterOptions {
 #[allow(dead_code)]
 pub struct Executer<'c> {
     ecs_client: EcsClient,
+    dry_run: bool,
     config: &'c config::command::ServiceConfig,
     options: &'c ExecuterOptions,
 }
@@ -22,13 +24,14 @@ pub struct Executer<'c> {
 impl<'c> Executer<'c> {
     pub fn from_config(
         config: &'c config::command::ServiceConfig,
+        client_factory: &ClientFactory,
         options: &'c ExecuterOptions,
     ) -> Self {
         trace!("command::service::stop::Executer::from_config");
 
-        let client = EcsClient::new(Region::ApNortheast1);
         Executer {
-            ecs_client: client,
+            ecs_client: client_factory.ecs(),
+            dry_run: client_factory.dry_run(),
             config: config,
             options: options,
         }
@@ -49,29 +52,36 @@ impl<'c> Executer<'c> {
         let maybe_task_definition = self
             .describe_latest_task_definition(&service_conf.task_definition.family)
             .await?;
-        if maybe_task_definition.is_none() {
-            output::PrintLine::error("Could not find task_definition");
-            return Err(Box::new(CommandError::Unknown));
-        }
-        let task_definition = maybe_task_definition.unwrap();
+        let task_definition = maybe_task_definition.ok_or(CommandError::TaskDefinitionNotFound(
+            service_conf.task_definition.family.to_owned(),
+        ))?;
 
         let zero_task_service = config::ecs::Service {
             name: service_conf.name.to_owned(),
             desired_count: Some(0),
             deployment_configuration: service_conf.deployment_configuration.to_owned(),
+            deployment_controller: service_conf.deployment_controller.to_owned(),
             load_balancers: service_conf.load_balancers.to_owned(),
             task_definition: service_conf.task_definition.to_owned(),
             role: service_conf.role.to_owned(),
             launch_type: service_conf.launch_type.to_owned(),
+            capacity_provider_strategy: service_conf.capacity_provider_strategy.to_owned(),
+            placement_constraints: service_conf.placement_constraints.to_owned(),
+            placement_strategy: service_conf.placement_strategy.to_owned(),
             network_configuration: service_conf.network_configuration.to_owned(),
             service_registries: service_conf.service_registries.to_owned(),
             platform_version: service_conf.platform_version.to_owned(),
             enable_execute_command: service_conf.enable_execute_command,
+            health_check_grace_period_seconds: service_conf.health_check_grace_period_seconds,
+            scheduling_strategy: service_conf.scheduling_strategy.to_owned(),
+            propagate_tags: service_conf.propagate_tags.to_owned(),
+            enable_ecs_managed_tags: service_conf.enable_ecs_managed_tags,
             tags: service_conf.tags.to_owned(),
         };
 
         output::PrintLine::info("Starting to update the service");
-        self.update_service(cluster, &zero_task_service, &task_definition)
+        let service = self
+            .update_service(cluster, &zero_task_service, &task_definition, false)
             .await?;
         output::PrintLine::info("Finished updating the service");
 
@@ -79,6 +89,12 @@ impl<'c> Executer<'c> {
         // }
 
         output::PrintLine::success("The service stopped");
+        output::PrintResult::emit(&json!({
+            "command": "service stop",
+            "name": self.config.name,
+            "cluster": cluster,
+            "service_arn": service.service_arn,
+        }));
         Ok(())
     }
 }
@@ -88,3 +104,9 @@ impl<'c> EcsExecuter for Executer<'c> {
         &self.ecs_client
     }
 }
+
+impl<'c> MutationExecuter for Executer<'c> {
+    fn dry_run(&self) -> bool {
+        self.dry_run
+    }
+}
@@ -0,0 +1,74 @@
+use std::error;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use tokio::time;
+
+use super::error::CommandError;
+
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Resolves the wait settings given from command line arguments and config, in this order.
+pub fn durations(
+    timeout_args: Option<u64>,
+    poll_interval_args: Option<u64>,
+    timeout_conf: Option<u64>,
+    poll_interval_conf: Option<u64>,
+) -> (Option<Duration>, Duration) {
+    let timeout = timeout_args.or(timeout_conf).map(Duration::from_secs);
+    let poll_interval = poll_interval_args
+        .or(poll_interval_conf)
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_POLL_INTERVAL);
+    (timeout, poll_interval)
+}
+
+/// Runs a waiting future, failing with `CommandError::Timeout` if it does not finish in time.
+pub async fn with_timeout<F, T>(
+    timeout: Option<Duration>,
+    future: F,
+) -> Result<T, Box<dyn error::Error>>
+where
+    F: Future<Output = Result<T, Box<dyn error::Error>>>,
+{
+    match timeout {
+        Some(duration) => match time::timeout(duration, future).await {
+            Ok(res) => res,
+            Err(_) => Err(Box::new(CommandError::Timeout(duration))),
+        },
+        None => future.await,
+    }
+}
+
+/// A time limit shared by a sequence of waits, which starts when it is made.
+pub struct Deadline {
+    timeout: Option<Duration>,
+    started_at: Instant,
+}
+
+impl Deadline {
+    pub fn after(timeout: Option<Duration>) -> Self {
+        Deadline {
+            timeout,
+            started_at: Instant::now(),
+        }
+    }
+
+    /// Runs a waiting future within the time left, failing with `CommandError::Timeout`
+    /// of the whole time limit if it does not finish in time.
+    pub async fn run<F, T>(&self, future: F) -> Result<T, Box<dyn error::Error>>
+    where
+        F: Future<Output = Result<T, Box<dyn error::Error>>>,
+    {
+        match self.timeout {
+            Some(timeout) => {
+                let left = timeout.saturating_sub(self.started_at.elapsed());
+                match time::timeout(left, future).await {
+                    Ok(res) => res,
+                    Err(_) => Err(Box::new(CommandError::Timeout(timeout))),
+                }
+            }
+            None => future.await,
+        }
+    }
+}
@@ -0,0 +1,25 @@
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AwsConfig {
+    pub region: Option<String>,
+    pub profile: Option<String>,
+    pub endpoint_url: Option<String>,
+}
+
+impl AwsConfig {
+    /// Returns a new config whose fields are taken from `other` if they are set there,
+    /// and from `self` otherwise.
+    pub fn merge(&self, other: Option<&AwsConfig>) -> AwsConfig {
+        match other {
+            Some(other) => AwsConfig {
+                region: other.region.to_owned().or(self.region.to_owned()),
+                profile: other.profile.to_owned().or(self.profile.to_owned()),
+                endpoint_url: other
+                    .endpoint_url
+                    .to_owned()
+                    .or(self.endpoint_url.to_owned()),
+            },
+            None => self.to_owned(),
+        }
+    }
+}
@@ -1,10 +1,59 @@
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct ScheduleRule {
   pub name: String,
   pub schedule_expression: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CapacityProviderStrategyItem {
+  pub capacity_provider: String,
+  pub weight: Option<i64>,
+  pub base: Option<i64>,
+}
+impl CapacityProviderStrategyItem {
+  pub fn to_rusoto(&self) -> rusoto_events::CapacityProviderStrategyItem {
+    rusoto_events::CapacityProviderStrategyItem {
+      capacity_provider: self.capacity_provider.to_owned(),
+      weight: self.weight,
+      base: self.base,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PlacementConstraint {
+  pub type_: Option<String>,
+  pub expression: Option<String>,
+}
+impl PlacementConstraint {
+  pub fn to_rusoto(&self) -> rusoto_events::PlacementConstraint {
+    rusoto_events::PlacementConstraint {
+      type_: self.type_.to_owned(),
+      expression: self.expression.to_owned(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PlacementStrategy {
+  pub type_: Option<String>,
+  pub field: Option<String>,
+}
+impl PlacementStrategy {
+  pub fn to_rusoto(&self) -> rusoto_events::PlacementStrategy {
+    rusoto_events::PlacementStrategy {
+      type_: self.type_.to_owned(),
+      field: self.field.to_owned(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct NetworkConfiguration {
   pub awsvpc_configuration: Option<AwsVpcConfiguration>,
 }
@@ -17,6 +66,7 @@ impl NetworkConfiguration {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct AwsVpcConfiguration {
   pub assign_public_ip: Option<String>,
   pub security_groups: Option<Vec<String>>,
@@ -0,0 +1,18 @@
+/// A CodeDeploy application and deployment group, which deploy a service with the
+/// `CODE_DEPLOY` deployment controller.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Deployment {
+    pub application_name: String,
+    pub deployment_group_name: String,
+    pub deployment_config_name: Option<String>,
+    pub hooks: Option<Vec<LifecycleHook>>,
+}
+
+/// A Lambda function invoked at the lifecycle event, listed in `Hooks` of the AppSpec.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LifecycleHook {
+    pub event: String,
+    pub function: String,
+}
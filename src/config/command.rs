@@ -10,13 +10,23 @@ use serde_yaml;
 
 use semver::{Version, VersionReq};
 
+use super::aws;
 use super::cloudwatch_events;
+use super::codedeploy;
 use super::ecs;
 
+const LAUNCH_TYPE_AND_CAPACITY_PROVIDER: &str =
+    "`launch_type` and `capacity_provider_strategy` cannot be set together";
+
 #[derive(Debug)]
 pub enum ConfigError {
     ParseError(serde_yaml::Error),
     VersionRequirementError,
+    InvalidEntry {
+        section: &'static str,
+        name: String,
+        reason: &'static str,
+    },
 }
 
 impl fmt::Display for ConfigError {
@@ -27,6 +37,11 @@ impl fmt::Display for ConfigError {
                 f,
                 "The specified version does not satisfy the current racco version"
             ),
+            ConfigError::InvalidEntry {
+                section,
+                ref name,
+                reason,
+            } => write!(f, "Invalid entry `{}` in `{}`: {}", name, section, reason),
         }
     }
 }
@@ -38,6 +53,7 @@ impl error::Error for ConfigError {
             ConfigError::VersionRequirementError => {
                 "The specified version does not satisfy the current racco version"
             }
+            ConfigError::InvalidEntry { .. } => "Invalid entry in config",
         }
     }
 
@@ -45,17 +61,28 @@ impl error::Error for ConfigError {
         match *self {
             ConfigError::ParseError(ref yaml_err) => Some(yaml_err),
             ConfigError::VersionRequirementError => None,
+            ConfigError::InvalidEntry { .. } => None,
         }
     }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct Config {
     pub version: Option<String>,
+    pub aws: Option<aws::AwsConfig>,
     pub service: Option<ServiceConfigGroup>,
     pub run_task: Option<RunTaskConfigGroup>,
     pub schedule_task: Option<ScheduleTaskConfigGroup>,
     pub params: Option<ParamsConfig>,
+    /// Names of entries selected together, which may contain wildcards.
+    pub groups: Option<BTreeMap<String, Vec<String>>>,
+
+    /// Overrides given from command line arguments, which take precedence over the file.
+    #[serde(skip)]
+    pub aws_override: Option<aws::AwsConfig>,
+    #[serde(skip)]
+    pub dry_run: bool,
 }
 
 impl Config {
@@ -86,6 +113,7 @@ impl Config {
                     "Config::from_file - Serialize reversely: {}",
                     serde_yaml::to_string(&c).unwrap()
                 );
+                c.validate()?;
                 Ok(c)
             }
             Err(e) => Err(Box::new(ConfigError::ParseError(e))),
@@ -140,6 +168,197 @@ impl Config {
         Ok(rendered)
     }
 
+    /// Resolves the AWS settings for an entry.
+    /// Command line arguments win over the entry's own `aws`, which wins over the top-level `aws`.
+    pub fn aws_config(&self, entry: Option<&aws::AwsConfig>) -> aws::AwsConfig {
+        self.aws
+            .to_owned()
+            .unwrap_or_default()
+            .merge(entry)
+            .merge(self.aws_override.as_ref())
+    }
+
+    /// Checks settings of entries which conflict with each other.
+    fn validate(&self) -> Result<(), ConfigError> {
+        for service_conf in self.service.iter().flatten() {
+            if service_conf.service.launch_type.is_some()
+                && service_conf.service.capacity_provider_strategy.is_some()
+            {
+                return Err(ConfigError::InvalidEntry {
+                    section: "service",
+                    name: service_conf.name.to_owned(),
+                    reason: LAUNCH_TYPE_AND_CAPACITY_PROVIDER,
+                });
+            }
+        }
+        for service_conf in self.service.iter().flatten() {
+            if service_conf.service.uses_code_deploy() {
+                if service_conf.code_deploy.is_none() {
+                    return Err(ConfigError::InvalidEntry {
+                        section: "service",
+                        name: service_conf.name.to_owned(),
+                        reason:
+                            "`code_deploy` is required for the CODE_DEPLOY deployment controller",
+                    });
+                }
+                let load_balancer = service_conf.service.load_balancers.iter().flatten().next();
+                if load_balancer
+                    .and_then(|lb| lb.container_name.as_ref().zip(lb.container_port))
+                    .is_none()
+                {
+                    return Err(ConfigError::InvalidEntry {
+                        section: "service",
+                        name: service_conf.name.to_owned(),
+                        reason: "`load_balancers` with a container is required for the CODE_DEPLOY deployment controller",
+                    });
+                }
+            }
+            match service_conf.service.rollout() {
+                Some(rollout) if !rollout.is_valid() => {
+                    return Err(ConfigError::InvalidEntry {
+                        section: "service",
+                        name: service_conf.name.to_owned(),
+                        reason: "`rollout` has steps which do not increase up to 100",
+                    });
+                }
+                Some(_) if !service_conf.service.uses_external_controller() => {
+                    return Err(ConfigError::InvalidEntry {
+                        section: "service",
+                        name: service_conf.name.to_owned(),
+                        reason: "`rollout` is only for the EXTERNAL deployment controller",
+                    });
+                }
+                None if service_conf.service.uses_external_controller() => {
+                    return Err(ConfigError::InvalidEntry {
+                        section: "service",
+                        name: service_conf.name.to_owned(),
+                        reason: "`rollout` is required for the EXTERNAL deployment controller",
+                    });
+                }
+                _ => {}
+            }
+            let unknown = service_conf
+                .depends_on
+                .iter()
+                .flatten()
+                .any(|dep| !self.service.iter().flatten().any(|c| &c.name == dep));
+            if unknown {
+                return Err(ConfigError::InvalidEntry {
+                    section: "service",
+                    name: service_conf.name.to_owned(),
+                    reason: "`depends_on` has an entry which does not exist",
+                });
+            }
+            if self.depends_on_itself(&service_conf.name) {
+                return Err(ConfigError::InvalidEntry {
+                    section: "service",
+                    name: service_conf.name.to_owned(),
+                    reason: "`depends_on` has a cycle",
+                });
+            }
+            let hooks = [
+                (
+                    &service_conf.before_deploy,
+                    "`before_deploy` has an entry of `run_task` which does not exist",
+                ),
+                (
+                    &service_conf.after_deploy,
+                    "`after_deploy` has an entry of `run_task` which does not exist",
+                ),
+            ];
+            for (hook_group, reason) in hooks {
+                let unknown = hook_group.iter().flatten().any(|hook| match hook {
+                    HookConfig::Name(name) => {
+                        !self.run_task.iter().flatten().any(|c| &c.name == name)
+                    }
+                    HookConfig::Task(_) => false,
+                });
+                if unknown {
+                    return Err(ConfigError::InvalidEntry {
+                        section: "service",
+                        name: service_conf.name.to_owned(),
+                        reason,
+                    });
+                }
+            }
+            let inline_hooks = service_conf
+                .before_deploy
+                .iter()
+                .chain(service_conf.after_deploy.iter())
+                .flatten()
+                .filter_map(|hook| match hook {
+                    HookConfig::Task(run_task_conf) => Some(run_task_conf),
+                    HookConfig::Name(_) => None,
+                });
+            for run_task_conf in inline_hooks {
+                if let Some(reason) = Self::run_task_error(run_task_conf) {
+                    return Err(ConfigError::InvalidEntry {
+                        section: "service",
+                        name: service_conf.name.to_owned(),
+                        reason,
+                    });
+                }
+            }
+        }
+        for run_task_conf in self.run_task.iter().flatten() {
+            if let Some(reason) = Self::run_task_error(run_task_conf) {
+                return Err(ConfigError::InvalidEntry {
+                    section: "run_task",
+                    name: run_task_conf.name.to_owned(),
+                    reason,
+                });
+            }
+        }
+        for schedule_task_conf in self.schedule_task.iter().flatten() {
+            if schedule_task_conf.launch_type.is_some()
+                && schedule_task_conf.capacity_provider_strategy.is_some()
+            {
+                return Err(ConfigError::InvalidEntry {
+                    section: "schedule_task",
+                    name: schedule_task_conf.name.to_owned(),
+                    reason: LAUNCH_TYPE_AND_CAPACITY_PROVIDER,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks settings of a `run_task` entry, which is also given inline as a hook.
+    fn run_task_error(run_task_conf: &RunTaskConfig) -> Option<&'static str> {
+        if run_task_conf.launch_type.is_some() && run_task_conf.capacity_provider_strategy.is_some()
+        {
+            return Some(LAUNCH_TYPE_AND_CAPACITY_PROVIDER);
+        }
+        if matches!(run_task_conf.count, Some(count) if count < 1) {
+            return Some("`count` should be 1 or more");
+        }
+        None
+    }
+
+    /// Tells whether the service entry reaches itself by following `depends_on`.
+    fn depends_on_itself(&self, name: &str) -> bool {
+        let mut visited: Vec<&str> = vec![];
+        let mut stack: Vec<&str> = vec![name];
+        while let Some(current) = stack.pop() {
+            let service_conf = self.service.iter().flatten().find(|c| c.name == current);
+            for dep in service_conf
+                .and_then(|c| c.depends_on.as_ref())
+                .into_iter()
+                .flatten()
+            {
+                if dep == name {
+                    return true;
+                }
+                if !visited.contains(&dep.as_str()) {
+                    visited.push(dep.as_str());
+                    stack.push(dep.as_str());
+                }
+            }
+        }
+        false
+    }
+
     fn validate_version(&self, current_ver_str: &str) -> Result<(), Box<dyn error::Error>> {
         if self.version.is_none() {
             return Ok(());
@@ -159,46 +378,87 @@ impl Config {
 pub type ServiceConfigGroup = Vec<ServiceConfig>;
 
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct ServiceConfig {
     pub name: String,
+    pub labels: Option<BTreeMap<String, String>>,
     pub cluster: String,
     pub service: ecs::Service,
+    pub aws: Option<aws::AwsConfig>,
+    pub timeout: Option<u64>,
+    pub poll_interval: Option<u64>,
+    pub max_failed_tasks: Option<i64>,
+    pub depends_on: Option<Vec<String>>,
+    pub before_deploy: Option<Vec<HookConfig>>,
+    pub after_deploy: Option<Vec<HookConfig>>,
+    pub code_deploy: Option<codedeploy::Deployment>,
+}
+
+/// A task run around a deployment, given by the name of a `run_task` entry or inline.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum HookConfig {
+    Name(String),
+    Task(Box<RunTaskConfig>),
 }
 
 pub type RunTaskConfigGroup = Vec<RunTaskConfig>;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct RunTaskConfig {
     pub name: String,
+    pub labels: Option<BTreeMap<String, String>>,
     pub cluster: String,
     pub task_definition: ecs::TaskDefinition,
     pub launch_type: Option<String>,
+    pub capacity_provider_strategy: Option<Vec<ecs::CapacityProviderStrategyItem>>,
+    pub placement_constraints: Option<Vec<ecs::PlacementConstraint>>,
+    pub placement_strategy: Option<Vec<ecs::PlacementStrategy>>,
     pub network_configuration: Option<ecs::NetworkConfiguration>,
     pub platform_version: Option<String>,
     pub enable_execute_command: Option<bool>,
+    pub overrides: Option<ecs::TaskOverride>,
+    pub count: Option<i64>,
+    pub started_by: Option<String>,
+    pub group: Option<String>,
+    pub tags: Option<ecs::Tags>,
+    pub propagate_tags: Option<String>,
+    pub aws: Option<aws::AwsConfig>,
+    pub timeout: Option<u64>,
+    pub poll_interval: Option<u64>,
 }
 
 pub type ScheduleTaskConfigGroup = Vec<ScheduleTaskConfig>;
 
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct ScheduleTaskConfig {
     pub name: String,
+    pub labels: Option<BTreeMap<String, String>>,
     pub cluster: String,
     pub launch_type: Option<String>,
+    pub capacity_provider_strategy: Option<Vec<cloudwatch_events::CapacityProviderStrategyItem>>,
+    pub placement_constraints: Option<Vec<cloudwatch_events::PlacementConstraint>>,
+    pub placement_strategy: Option<Vec<cloudwatch_events::PlacementStrategy>>,
     pub platform_version: Option<String>,
     pub network_configuration: Option<cloudwatch_events::NetworkConfiguration>,
     pub task_definition: ecs::TaskDefinition,
     pub rule: cloudwatch_events::ScheduleRule,
     pub rule_targets_role_arn: Option<String>,
+    pub aws: Option<aws::AwsConfig>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct ParamsConfig {
     pub path: String,
     pub secure: Option<ParamsSecure>,
+    pub aws: Option<aws::AwsConfig>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct ParamsSecure {
     pub key: String,
 }
@@ -244,6 +504,189 @@ fn test_service_config() {
     });
 }
 
+#[test]
+fn test_service_config_depends_on() {
+    let entry = |name: &str, depends_on: &str| {
+        format!(
+            r"  - name: {}
+    cluster: test-cluster
+    depends_on: [{}]
+    service:
+      name: {}
+      task_definition:
+        family: {}
+        container_definitions:
+          - name: test
+            image: 'test.dkr.com/racco/test:latest'
+",
+            name, depends_on, name, name
+        )
+    };
+    let vars = json!({});
+
+    let tmpl = format!("service:\n{}{}", entry("api", "db"), entry("db", ""));
+    assert!(Config::new(&tmpl, &vars).is_ok());
+
+    let tmpl = format!("service:\n{}", entry("api", "unknown"));
+    assert!(Config::new(&tmpl, &vars).is_err());
+
+    let tmpl = format!(
+        "service:\n{}{}{}",
+        entry("api", "worker"),
+        entry("worker", "db"),
+        entry("db", "api")
+    );
+    assert!(Config::new(&tmpl, &vars).is_err());
+}
+
+#[test]
+fn test_service_config_hooks() {
+    let tmpl = r"service:
+  - name: test
+    cluster: test-cluster
+    before_deploy:
+      - migration
+    after_deploy:
+      - name: warmer
+        cluster: test-cluster
+        task_definition:
+          family: warmer
+          container_definitions:
+            - name: test
+              image: 'test.dkr.com/racco/test:latest'
+    service:
+      name: test
+      task_definition:
+        family: test
+        container_definitions:
+          - name: test
+            image: 'test.dkr.com/racco/test:latest'
+run_task:
+  - name: migration
+    cluster: test-cluster
+    task_definition:
+      family: migration
+      container_definitions:
+        - name: test
+          image: 'test.dkr.com/racco/test:latest'
+";
+    let vars = json!({});
+
+    let config = Config::new(tmpl, &vars).unwrap();
+    let service_conf = &config.service.as_ref().unwrap()[0];
+    assert!(matches!(
+        service_conf.before_deploy.as_ref().unwrap()[0],
+        HookConfig::Name(ref name) if name == "migration"
+    ));
+    assert!(matches!(
+        service_conf.after_deploy.as_ref().unwrap()[0],
+        HookConfig::Task(ref task) if task.name == "warmer"
+    ));
+
+    let tmpl_unknown = tmpl.replace("- migration", "- unknown");
+    assert!(Config::new(&tmpl_unknown, &vars).is_err());
+    let tmpl_count = tmpl.replace(
+        "      - name: warmer\n",
+        "      - name: warmer\n        count: 0\n",
+    );
+    assert!(Config::new(&tmpl_count, &vars).is_err());
+}
+
+#[test]
+fn test_groups_and_labels() {
+    let tmpl = r"groups:
+  backend: [api, worker, cron-*]
+service:
+  - name: api
+    labels:
+      tier: web
+    cluster: test-cluster
+    service:
+      name: api
+      task_definition:
+        family: api
+        container_definitions:
+          - name: test
+            image: 'test.dkr.com/racco/test:latest'
+";
+    let vars = json!({});
+
+    let config = Config::new(tmpl, &vars).unwrap();
+    assert_eq!(
+        config.groups.as_ref().unwrap()["backend"],
+        vec!["api", "worker", "cron-*"]
+    );
+    let labels = config.service.as_ref().unwrap()[0].labels.as_ref().unwrap();
+    assert_eq!(labels.get("tier").map(String::as_str), Some("web"));
+}
+
+#[test]
+fn test_service_config_code_deploy() {
+    let tmpl = r"service:
+  - name: test
+    cluster: test-cluster
+    code_deploy:
+      application_name: test-app
+      deployment_group_name: test-group
+    service:
+      name: test
+      deployment_controller:
+        type_: CODE_DEPLOY
+      load_balancers:
+        - container_name: test
+          container_port: 80
+          target_group_arn: 'arn:aws:elasticloadbalancing:ap-northeast-1:000000000000:targetgroup/test/0'
+      task_definition:
+        family: test
+        container_definitions:
+          - name: test
+            image: 'test.dkr.com/racco/test:latest'
+";
+    let vars = json!({});
+
+    let config = Config::new(tmpl, &vars).unwrap();
+    assert!(config.service.as_ref().unwrap()[0]
+        .service
+        .uses_code_deploy());
+
+    let tmpl = tmpl.replace("    code_deploy:\n      application_name: test-app\n      deployment_group_name: test-group\n", "");
+    assert!(Config::new(&tmpl, &vars).is_err());
+}
+
+#[test]
+fn test_service_config_rollout() {
+    let tmpl = r"service:
+  - name: test
+    cluster: test-cluster
+    service:
+      name: test
+      deployment_configuration:
+        rollout:
+          steps: [10, 50, 100]
+          wait_seconds: 30
+      deployment_controller:
+        type_: EXTERNAL
+      task_definition:
+        family: test
+        container_definitions:
+          - name: test
+            image: 'test.dkr.com/racco/test:latest'
+";
+    let vars = json!({});
+
+    let config = Config::new(tmpl, &vars).unwrap();
+    let service = &config.service.as_ref().unwrap()[0].service;
+    assert!(service.uses_external_controller());
+    assert_eq!(service.rollout().unwrap().steps, vec![10.0, 50.0, 100.0]);
+
+    let tmpl_unordered = tmpl.replace("[10, 50, 100]", "[50, 10, 100]");
+    assert!(Config::new(&tmpl_unordered, &vars).is_err());
+    let tmpl_partial = tmpl.replace("[10, 50, 100]", "[10, 50]");
+    assert!(Config::new(&tmpl_partial, &vars).is_err());
+    let tmpl_ecs = tmpl.replace("type_: EXTERNAL", "type_: ECS");
+    assert!(Config::new(&tmpl_ecs, &vars).is_err());
+}
+
 #[test]
 fn test_run_task_config() {
     let tmpl = r"run_task:
@@ -267,6 +710,25 @@ fn test_run_task_config() {
     });
 }
 
+#[test]
+fn test_run_task_config_count() {
+    let tmpl = r"run_task:
+  - name: test
+    cluster: test-cluster
+    count: 3
+    task_definition:
+      family: test
+      container_definitions:
+        - name: test
+          image: 'test.dkr.com/racco/test:latest'
+";
+    let vars = json!({});
+
+    assert!(Config::new(tmpl, &vars).is_ok());
+    assert!(Config::new(&tmpl.replace("count: 3", "count: 0"), &vars).is_err());
+    assert!(Config::new(&tmpl.replace("count: 3", "count: -1"), &vars).is_err());
+}
+
 #[test]
 fn test_schedule_task_config() {
     let tmpl = r"schedule_task:
@@ -356,3 +818,84 @@ fn test_version_requirement_not_satisfied_less() {
         _ => false,
     });
 }
+
+#[test]
+fn test_aws_config() {
+    let tmpl = r"aws:
+  region: us-east-1
+  profile: production
+service:
+  - name: test
+    cluster: test-cluster
+    aws:
+      region: eu-west-1
+    service:
+      name: test
+      task_definition:
+        family: test
+        container_definitions:
+          - name: test
+            image: 'test.dkr.com/racco/test:latest'
+";
+    let vars = json!({});
+
+    let mut config = Config::new(tmpl, &vars).unwrap();
+    let entry_aws = config.service.as_ref().unwrap()[0].aws.to_owned();
+
+    let resolved = config.aws_config(entry_aws.as_ref());
+    assert_eq!(resolved.region, Some(String::from("eu-west-1")));
+    assert_eq!(resolved.profile, Some(String::from("production")));
+    assert_eq!(resolved.endpoint_url, None);
+
+    config.aws_override = Some(aws::AwsConfig {
+        region: Some(String::from("ap-northeast-1")),
+        endpoint_url: Some(String::from("http://localhost:4566")),
+        ..Default::default()
+    });
+    let resolved = config.aws_config(entry_aws.as_ref());
+    assert_eq!(resolved.region, Some(String::from("ap-northeast-1")));
+    assert_eq!(resolved.profile, Some(String::from("production")));
+    assert_eq!(
+        resolved.endpoint_url,
+        Some(String::from("http://localhost:4566"))
+    );
+}
+
+#[test]
+fn test_launch_type_with_capacity_provider_strategy() {
+    let tmpl = r"run_task:
+  - name: test
+    cluster: test-cluster
+    launch_type: FARGATE
+    capacity_provider_strategy:
+      - capacity_provider: FARGATE_SPOT
+        weight: 1
+    task_definition:
+      family: test
+      container_definitions:
+        - name: test
+          image: 'test.dkr.com/racco/test:latest'
+";
+    let vars = json!({});
+
+    let ret = Config::new(tmpl, &vars);
+    assert!(ret.is_err());
+}
+
+#[test]
+fn test_unknown_field() {
+    let tmpl = r"run_task:
+  - name: test
+    cluster: test-cluster
+    task_definition:
+      family: test
+      netwrok_mode: awsvpc
+      container_definitions:
+        - name: test
+          image: 'test.dkr.com/racco/test:latest'
+";
+    let vars = json!({});
+
+    let ret = Config::new(tmpl, &vars);
+    assert!(ret.is_err());
+}
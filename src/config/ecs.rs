@@ -1,22 +1,56 @@
 use rusoto_ecs;
+use serde_json::Value;
 
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct Service {
     pub name: String,
     pub desired_count: Option<i64>,
     pub deployment_configuration: Option<DeploymentConfiguration>,
+    pub deployment_controller: Option<DeploymentController>,
     pub load_balancers: Option<LoadBalancers>,
     pub task_definition: TaskDefinition,
     pub role: Option<String>,
     pub launch_type: Option<String>,
+    pub capacity_provider_strategy: Option<Vec<CapacityProviderStrategyItem>>,
+    pub placement_constraints: Option<Vec<PlacementConstraint>>,
+    pub placement_strategy: Option<Vec<PlacementStrategy>>,
     pub network_configuration: Option<NetworkConfiguration>,
     pub service_registries: Option<Vec<ServiceRegistry>>,
     pub platform_version: Option<String>,
     pub enable_execute_command: Option<bool>,
+    pub health_check_grace_period_seconds: Option<i64>,
+    pub scheduling_strategy: Option<String>,
+    pub propagate_tags: Option<String>,
+    pub enable_ecs_managed_tags: Option<bool>,
     pub tags: Option<Tags>,
 }
+impl Service {
+    /// Tells whether deployments of the service are made by CodeDeploy.
+    pub fn uses_code_deploy(&self) -> bool {
+        self.deployment_controller
+            .as_ref()
+            .map(|dc| dc.type_.as_str())
+            == Some("CODE_DEPLOY")
+    }
+
+    /// Tells whether racco rolls out the service by itself with task sets.
+    pub fn uses_external_controller(&self) -> bool {
+        self.deployment_controller
+            .as_ref()
+            .map(|dc| dc.type_.as_str())
+            == Some("EXTERNAL")
+    }
+
+    pub fn rollout(&self) -> Option<&Rollout> {
+        self.deployment_configuration
+            .as_ref()
+            .and_then(|dc| dc.rollout.as_ref())
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct TaskDefinition {
     pub family: String,
     pub container_definitions: ContainerDefinitions,
@@ -28,9 +62,166 @@ pub struct TaskDefinition {
     pub cpu: Option<String>,
     pub memory: Option<String>,
     pub proxy_configuration: Option<ProxyConfiguration>,
+    pub placement_constraints: Option<Vec<TaskDefinitionPlacementConstraint>>,
+    pub pid_mode: Option<String>,
+    pub ipc_mode: Option<String>,
+    pub ephemeral_storage: Option<EphemeralStorage>,
+    pub inference_accelerators: Option<Vec<InferenceAccelerator>>,
+    /// Not in the request of the SDK, so it is sent apart from `to_rusoto`.
+    pub runtime_platform: Option<RuntimePlatform>,
+    pub tags: Option<Tags>,
+}
+impl TaskDefinition {
+    pub fn to_rusoto(&self) -> rusoto_ecs::RegisterTaskDefinitionRequest {
+        rusoto_ecs::RegisterTaskDefinitionRequest {
+            family: self.family.to_owned(),
+            task_role_arn: self.task_role_arn.to_owned(),
+            network_mode: self.network_mode.to_owned(),
+            volumes: self
+                .volumes
+                .as_ref()
+                .map(|volumes| volumes.iter().map(|v| v.to_rusoto()).collect()),
+            container_definitions: self
+                .container_definitions
+                .iter()
+                .map(|cd| cd.to_rusoto())
+                .collect(),
+            execution_role_arn: self.execution_role_arn.to_owned(),
+            requires_compatibilities: self.requires_compatibilities.to_owned(),
+            cpu: self.cpu.to_owned(),
+            memory: self.memory.to_owned(),
+            proxy_configuration: self.proxy_configuration.as_ref().map(|pc| pc.to_rusoto()),
+            placement_constraints: self
+                .placement_constraints
+                .as_ref()
+                .map(|pcs| pcs.iter().map(|pc| pc.to_rusoto()).collect()),
+            pid_mode: self.pid_mode.to_owned(),
+            ipc_mode: self.ipc_mode.to_owned(),
+            ephemeral_storage: self.ephemeral_storage.as_ref().map(|es| es.to_rusoto()),
+            inference_accelerators: self
+                .inference_accelerators
+                .as_ref()
+                .map(|ias| ias.iter().map(|ia| ia.to_rusoto()).collect()),
+            tags: self
+                .tags
+                .as_ref()
+                .map(|ts| ts.iter().map(|t| t.to_rusoto()).collect()),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuntimePlatform {
+    pub cpu_architecture: Option<String>,
+    pub operating_system_family: Option<String>,
+}
+impl RuntimePlatform {
+    /// Returns the value in the API, which the SDK has no type of.
+    pub fn to_json(&self) -> Value {
+        let mut value = json!({});
+        if let Some(cpu_architecture) = self.cpu_architecture.as_ref() {
+            value["cpuArchitecture"] = json!(cpu_architecture);
+        }
+        if let Some(operating_system_family) = self.operating_system_family.as_ref() {
+            value["operatingSystemFamily"] = json!(operating_system_family);
+        }
+        value
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EphemeralStorage {
+    pub size_in_gib: i64,
+}
+impl EphemeralStorage {
+    pub fn to_rusoto(&self) -> rusoto_ecs::EphemeralStorage {
+        rusoto_ecs::EphemeralStorage {
+            size_in_gi_b: self.size_in_gib,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct InferenceAccelerator {
+    pub device_name: String,
+    pub device_type: String,
+}
+impl InferenceAccelerator {
+    pub fn to_rusoto(&self) -> rusoto_ecs::InferenceAccelerator {
+        rusoto_ecs::InferenceAccelerator {
+            device_name: self.device_name.to_owned(),
+            device_type: self.device_type.to_owned(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CapacityProviderStrategyItem {
+    pub capacity_provider: String,
+    pub weight: Option<i64>,
+    pub base: Option<i64>,
+}
+impl CapacityProviderStrategyItem {
+    pub fn to_rusoto(&self) -> rusoto_ecs::CapacityProviderStrategyItem {
+        rusoto_ecs::CapacityProviderStrategyItem {
+            capacity_provider: self.capacity_provider.to_owned(),
+            weight: self.weight,
+            base: self.base,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PlacementConstraint {
+    pub type_: Option<String>,
+    pub expression: Option<String>,
+}
+impl PlacementConstraint {
+    pub fn to_rusoto(&self) -> rusoto_ecs::PlacementConstraint {
+        rusoto_ecs::PlacementConstraint {
+            type_: self.type_.to_owned(),
+            expression: self.expression.to_owned(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PlacementStrategy {
+    pub type_: Option<String>,
+    pub field: Option<String>,
+}
+impl PlacementStrategy {
+    pub fn to_rusoto(&self) -> rusoto_ecs::PlacementStrategy {
+        rusoto_ecs::PlacementStrategy {
+            type_: self.type_.to_owned(),
+            field: self.field.to_owned(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TaskDefinitionPlacementConstraint {
+    pub type_: Option<String>,
+    pub expression: Option<String>,
+}
+impl TaskDefinitionPlacementConstraint {
+    pub fn to_rusoto(&self) -> rusoto_ecs::TaskDefinitionPlacementConstraint {
+        rusoto_ecs::TaskDefinitionPlacementConstraint {
+            type_: self.type_.to_owned(),
+            expression: self.expression.to_owned(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct NetworkConfiguration {
     pub awsvpc_configuration: Option<AwsVpcConfiguration>,
 }
@@ -43,6 +234,7 @@ impl NetworkConfiguration {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct AwsVpcConfiguration {
     pub assign_public_ip: Option<String>,
     pub security_groups: Option<Vec<String>>,
@@ -58,7 +250,109 @@ impl AwsVpcConfiguration {
     }
 }
 
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TaskOverride {
+    pub container_overrides: Option<Vec<ContainerOverride>>,
+    pub cpu: Option<String>,
+    pub memory: Option<String>,
+    pub task_role_arn: Option<String>,
+    pub execution_role_arn: Option<String>,
+}
+impl TaskOverride {
+    pub fn to_rusoto(&self) -> rusoto_ecs::TaskOverride {
+        rusoto_ecs::TaskOverride {
+            container_overrides: self
+                .container_overrides
+                .as_ref()
+                .map(|cos| cos.iter().map(|co| co.to_rusoto()).collect()),
+            cpu: self.cpu.to_owned(),
+            memory: self.memory.to_owned(),
+            task_role_arn: self.task_role_arn.to_owned(),
+            execution_role_arn: self.execution_role_arn.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    /// Merges other overrides on top of these.
+    /// Commands are replaced, and environment variables are replaced by name.
+    pub fn merge(&self, other: &TaskOverride) -> TaskOverride {
+        let mut container_overrides = self.container_overrides.to_owned().unwrap_or_default();
+        for other_co in other.container_overrides.iter().flatten() {
+            match container_overrides
+                .iter_mut()
+                .find(|co| co.name == other_co.name)
+            {
+                Some(co) => co.merge(other_co),
+                None => container_overrides.push(other_co.to_owned()),
+            }
+        }
+
+        TaskOverride {
+            container_overrides: if container_overrides.is_empty() {
+                None
+            } else {
+                Some(container_overrides)
+            },
+            cpu: other.cpu.to_owned().or_else(|| self.cpu.to_owned()),
+            memory: other.memory.to_owned().or_else(|| self.memory.to_owned()),
+            task_role_arn: other
+                .task_role_arn
+                .to_owned()
+                .or_else(|| self.task_role_arn.to_owned()),
+            execution_role_arn: other
+                .execution_role_arn
+                .to_owned()
+                .or_else(|| self.execution_role_arn.to_owned()),
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ContainerOverride {
+    pub name: String,
+    pub command: Option<Vec<String>>,
+    pub environment: Option<EnvironmentVariables>,
+    pub cpu: Option<i64>,
+    pub memory: Option<i64>,
+    pub memory_reservation: Option<i64>,
+}
+impl ContainerOverride {
+    pub fn to_rusoto(&self) -> rusoto_ecs::ContainerOverride {
+        rusoto_ecs::ContainerOverride {
+            name: Some(self.name.to_owned()),
+            command: self.command.to_owned(),
+            environment: self
+                .environment
+                .as_ref()
+                .map(|env| env.iter().map(|e| e.to_rusoto()).collect()),
+            cpu: self.cpu,
+            memory: self.memory,
+            memory_reservation: self.memory_reservation,
+            ..Default::default()
+        }
+    }
+
+    fn merge(&mut self, other: &ContainerOverride) {
+        if other.command.is_some() {
+            self.command = other.command.to_owned();
+        }
+        if let Some(other_env) = other.environment.as_ref() {
+            let environment = self.environment.get_or_insert_with(Vec::new);
+            for other_kv in other_env.iter() {
+                environment.retain(|kv| kv.name != other_kv.name);
+                environment.push(other_kv.to_owned());
+            }
+        }
+        self.cpu = other.cpu.or(self.cpu);
+        self.memory = other.memory.or(self.memory);
+        self.memory_reservation = other.memory_reservation.or(self.memory_reservation);
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct ServiceRegistry {
     pub container_name: Option<String>,
     pub container_port: Option<i64>,
@@ -79,6 +373,7 @@ impl ServiceRegistry {
 pub type NetworkMode = String;
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct Volume {
     pub docker_volume_configuration: Option<DockerVolumeConfiguration>,
     pub efs_volume_configuration: Option<EFSVolumeConfiguration>,
@@ -104,6 +399,7 @@ impl Volume {
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct DockerVolumeConfiguration {
     pub autoprovision: Option<bool>,
     pub driver: Option<String>,
@@ -124,6 +420,7 @@ impl DockerVolumeConfiguration {
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct EFSVolumeConfiguration {
     pub authorization_config: Option<EFSAuthorizationConfig>,
     pub file_system_id: String,
@@ -144,6 +441,7 @@ impl EFSVolumeConfiguration {
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct EFSAuthorizationConfig {
     pub access_point_id: Option<String>,
     pub iam: Option<String>,
@@ -158,6 +456,7 @@ impl EFSAuthorizationConfig {
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct HostVolumeProperties {
     pub source_path: Option<String>,
 }
@@ -171,6 +470,7 @@ impl HostVolumeProperties {
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct ProxyConfiguration {
     pub container_name: String,
     pub properties: Option<Vec<KeyValuePair>>,
@@ -192,10 +492,13 @@ impl ProxyConfiguration {
 // rusoto compatible structs
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct DeploymentConfiguration {
     pub maximum_percent: Option<i64>,
     pub minimum_healthy_percent: Option<i64>,
     pub deployment_circuit_breaker: Option<DeploymentCircuitBreaker>,
+    /// Not a part of the API, but how racco rolls out a service of the EXTERNAL deployment controller.
+    pub rollout: Option<Rollout>,
 }
 impl DeploymentConfiguration {
     pub fn to_rusoto(&self) -> rusoto_ecs::DeploymentConfiguration {
@@ -210,7 +513,42 @@ impl DeploymentConfiguration {
     }
 }
 
+/// Shifts the scale of a new task set in steps of percentages, waiting at each of them.
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Rollout {
+    pub steps: Vec<f64>,
+    pub wait_seconds: Option<u64>,
+}
+impl Rollout {
+    /// Tells whether the steps increase up to 100%.
+    pub fn is_valid(&self) -> bool {
+        let mut previous = 0.0;
+        for step in self.steps.iter() {
+            if *step <= previous || *step > 100.0 {
+                return false;
+            }
+            previous = *step;
+        }
+        previous == 100.0
+    }
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DeploymentController {
+    pub type_: String,
+}
+impl DeploymentController {
+    pub fn to_rusoto(&self) -> rusoto_ecs::DeploymentController {
+        rusoto_ecs::DeploymentController {
+            type_: self.type_.to_owned(),
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct DeploymentCircuitBreaker {
     pub enable: bool,
     pub rollback: bool,
@@ -225,6 +563,7 @@ impl DeploymentCircuitBreaker {
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct LoadBalancer {
     pub container_name: Option<String>,
     pub container_port: Option<i64>,
@@ -246,6 +585,7 @@ impl LoadBalancer {
 pub type LoadBalancers = Vec<LoadBalancer>;
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct ContainerDefinition {
     pub command: Option<StringList>,
     pub cpu: Option<Integer>,
@@ -367,6 +707,7 @@ impl ContainerDefinition {
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct ContainerDependency {
     pub condition: String,
     pub container_name: String,
@@ -381,6 +722,7 @@ impl ContainerDependency {
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct FirelensConfiguration {
     pub options: Option<::std::collections::HashMap<String, String>>,
     pub type_: String,
@@ -395,6 +737,7 @@ impl FirelensConfiguration {
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct RepositoryCredentials {
     pub credentials_parameter: String,
 }
@@ -407,6 +750,7 @@ impl RepositoryCredentials {
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct ResourceRequirement {
     pub type_: String,
     pub value: String,
@@ -421,6 +765,7 @@ impl ResourceRequirement {
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct Secret {
     pub name: String,
     pub value_from: String,
@@ -435,6 +780,7 @@ impl Secret {
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct EnvironmentFile {
     pub type_: String,
     pub value: String,
@@ -449,6 +795,7 @@ impl EnvironmentFile {
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct SystemControl {
     pub namespace: Option<String>,
     pub value: Option<String>,
@@ -473,6 +820,7 @@ pub type DockerLabelsMap = ::std::collections::HashMap<String, String>;
 pub type EnvironmentVariables = Vec<KeyValuePair>;
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct KeyValuePair {
     pub name: Option<String>,
     pub value: Option<String>,
@@ -487,6 +835,7 @@ impl KeyValuePair {
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct HostEntry {
     pub hostname: String,
     pub ip_address: String,
@@ -503,6 +852,7 @@ impl HostEntry {
 pub type HostEntryList = Vec<HostEntry>;
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct MountPoint {
     pub container_path: Option<String>,
     pub read_only: Option<BoxedBoolean>,
@@ -521,6 +871,7 @@ impl MountPoint {
 pub type MountPointList = Vec<MountPoint>;
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct PortMapping {
     pub container_port: Option<BoxedInteger>,
     pub host_port: Option<BoxedInteger>,
@@ -540,6 +891,7 @@ pub type PortMappingList = Vec<PortMapping>;
 pub type TransportProtocol = String;
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct Ulimit {
     pub hard_limit: Integer,
     pub name: UlimitName,
@@ -559,6 +911,7 @@ pub type UlimitList = Vec<Ulimit>;
 pub type UlimitName = String;
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct VolumeFrom {
     pub read_only: Option<BoxedBoolean>,
     pub source_container: Option<String>,
@@ -575,6 +928,7 @@ impl VolumeFrom {
 pub type VolumeFromList = Vec<VolumeFrom>;
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct LogConfiguration {
     pub log_driver: LogDriver,
     pub options: Option<LogConfigurationOptionsMap>,
@@ -597,6 +951,7 @@ pub type LogConfigurationOptionsMap = ::std::collections::HashMap<String, String
 pub type LogDriver = String;
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct HealthCheck {
     pub command: Vec<String>,
     pub interval: Option<i64>,
@@ -617,6 +972,7 @@ impl HealthCheck {
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct LinuxParameters {
     pub capabilities: Option<KernelCapabilities>,
     pub devices: Option<Vec<Device>>,
@@ -647,6 +1003,7 @@ impl LinuxParameters {
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct KernelCapabilities {
     pub add: Option<Vec<String>>,
     pub drop: Option<Vec<String>>,
@@ -661,6 +1018,7 @@ impl KernelCapabilities {
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct Device {
     pub container_path: Option<String>,
     pub host_path: String,
@@ -677,6 +1035,7 @@ impl Device {
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct Tmpfs {
     pub container_path: String,
     pub mount_options: Option<Vec<String>>,
@@ -693,6 +1052,7 @@ impl Tmpfs {
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct Tag {
     pub key: Option<String>,
     pub value: Option<String>,
@@ -708,3 +1068,56 @@ impl Tag {
 }
 
 pub type Tags = Vec<Tag>;
+
+#[test]
+fn test_merge_task_override() {
+    let env = |name: &str, value: &str| KeyValuePair {
+        name: Some(name.to_owned()),
+        value: Some(value.to_owned()),
+    };
+    let base = TaskOverride {
+        container_overrides: Some(vec![ContainerOverride {
+            name: String::from("app"),
+            command: Some(vec![String::from("rake"), String::from("db:migrate")]),
+            environment: Some(vec![env("RAILS_ENV", "production"), env("VERSION", "1")]),
+            ..Default::default()
+        }]),
+        cpu: Some(String::from("256")),
+        ..Default::default()
+    };
+    let other = TaskOverride {
+        container_overrides: Some(vec![ContainerOverride {
+            name: String::from("app"),
+            environment: Some(vec![env("VERSION", "42")]),
+            ..Default::default()
+        }]),
+        memory: Some(String::from("1024")),
+        ..Default::default()
+    };
+
+    let merged = base.merge(&other);
+    let container_overrides = merged.container_overrides.unwrap();
+    assert_eq!(container_overrides.len(), 1);
+    assert_eq!(
+        container_overrides[0].command,
+        Some(vec![String::from("rake"), String::from("db:migrate")])
+    );
+    let environment: Vec<(Option<String>, Option<String>)> = container_overrides[0]
+        .environment
+        .iter()
+        .flatten()
+        .map(|kv| (kv.name.to_owned(), kv.value.to_owned()))
+        .collect();
+    assert_eq!(
+        environment,
+        vec![
+            (
+                Some(String::from("RAILS_ENV")),
+                Some(String::from("production"))
+            ),
+            (Some(String::from("VERSION")), Some(String::from("42"))),
+        ]
+    );
+    assert_eq!(merged.cpu, Some(String::from("256")));
+    assert_eq!(merged.memory, Some(String::from("1024")));
+}
@@ -1,3 +1,5 @@
+pub mod aws;
 pub mod cloudwatch_events;
+pub mod codedeploy;
 pub mod command;
 pub mod ecs;
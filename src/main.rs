@@ -12,12 +12,16 @@ extern crate handlebars;
 
 extern crate clap;
 
+extern crate futures;
+
 extern crate hyper;
 
+extern crate rusoto_codedeploy;
 extern crate rusoto_core;
 extern crate rusoto_credential;
 extern crate rusoto_ecs;
 extern crate rusoto_events;
+extern crate rusoto_logs;
 extern crate rusoto_ssm;
 
 extern crate tabwriter;
@@ -29,14 +33,52 @@ pub mod command;
 pub mod config;
 pub mod output;
 
-use crate::command::MainCommand;
+use std::error;
+use std::time::Instant;
+
+use crate::command::{CommandError, MainCommand};
+use crate::config::command::ConfigError;
+
+fn exit_code(err: &(dyn error::Error + 'static)) -> i32 {
+    if let Some(command_err) = CommandError::find(err) {
+        return command_err.exit_code();
+    }
+    if err.downcast_ref::<ConfigError>().is_some() {
+        return 3;
+    }
+    1
+}
+
+fn error_kind(err: &(dyn error::Error + 'static)) -> &'static str {
+    if let Some(command_err) = CommandError::find(err) {
+        return command_err.kind();
+    }
+    if err.downcast_ref::<ConfigError>().is_some() {
+        return "config";
+    }
+    "unknown"
+}
 
 #[tokio::main]
 async fn main() {
     env_logger::init().unwrap();
 
-    ::std::process::exit(match MainCommand::run().await {
+    let started_at = Instant::now();
+    let res = MainCommand::run().await;
+    let code = match res.as_ref() {
         Ok(_res) => 0,
-        Err(_err) => 1,
-    });
+        Err(err) => exit_code(err.as_ref()),
+    };
+
+    output::PrintResult::emit(&json!({
+        "status": if code == 0 { "succeeded" } else { "failed" },
+        "exit_code": code,
+        "elapsed_seconds": started_at.elapsed().as_secs_f64(),
+        "error": res.as_ref().err().map(|err| json!({
+            "kind": error_kind(err.as_ref()),
+            "message": err.to_string(),
+        })),
+    }));
+
+    ::std::process::exit(code);
 }
@@ -0,0 +1,44 @@
+use std::io;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static JSON: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Format, String> {
+        match format {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("Unknown output format: {}", format)),
+        }
+    }
+}
+
+/// Sets the output format of the process, which is given from command line arguments.
+pub fn set_format(format: Format) {
+    JSON.store(format == Format::Json, Ordering::Relaxed);
+}
+
+pub fn format() -> Format {
+    if JSON.load(Ordering::Relaxed) {
+        Format::Json
+    } else {
+        Format::Text
+    }
+}
+
+/// Writer for text for humans such as tables, which goes to stderr in JSON output.
+pub fn text_writer() -> Box<dyn io::Write> {
+    match format() {
+        Format::Text => Box::new(io::stdout()),
+        Format::Json => Box::new(io::stderr()),
+    }
+}
@@ -1,3 +1,11 @@
+mod format;
+mod print_diff;
 mod print_line;
+mod print_result;
+mod timestamp;
 
+pub use self::format::{format, set_format, text_writer, Format};
+pub use self::print_diff::PrintDiff;
 pub use self::print_line::PrintLine;
+pub use self::print_result::PrintResult;
+pub use self::timestamp::format_timestamp;
@@ -0,0 +1,38 @@
+use termion::color;
+
+use super::print_line::PrintLine;
+
+pub struct PrintDiff {}
+
+impl PrintDiff {
+    pub fn added(path: &str, value: &str) {
+        PrintLine::print(&format!(
+            "{}  + {}: {}{}",
+            color::Fg(color::Green),
+            path,
+            value,
+            color::Fg(color::Reset)
+        ))
+    }
+
+    pub fn removed(path: &str, value: &str) {
+        PrintLine::print(&format!(
+            "{}  - {}: {}{}",
+            color::Fg(color::Red),
+            path,
+            value,
+            color::Fg(color::Reset)
+        ))
+    }
+
+    pub fn changed(path: &str, from: &str, to: &str) {
+        PrintLine::print(&format!(
+            "{}  ~ {}: {} -> {}{}",
+            color::Fg(color::Yellow),
+            path,
+            from,
+            to,
+            color::Fg(color::Reset)
+        ))
+    }
+}
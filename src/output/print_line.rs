@@ -1,19 +1,34 @@
+use std::future::Future;
+
 use termion::color;
 
+use super::format::{format, Format};
+
+tokio::task_local! {
+    static PREFIX: String;
+}
+
 pub struct PrintLine {}
 
 impl PrintLine {
+    /// Runs a future printing lines prefixed with the name,
+    /// so that they are told apart from the ones of futures running concurrently.
+    pub async fn with_prefix<F: Future>(name: &str, future: F) -> F::Output {
+        PREFIX.scope(format!("[{}] ", name), future).await
+    }
+
     pub fn print(msg: &str) {
-        println!("{}", msg)
+        Self::out(msg)
     }
 
     pub fn info(msg: &str) {
-        println!("{}", msg)
+        Self::out(msg)
     }
 
     pub fn warn(msg: &str) {
         eprintln!(
-            "{}{}{}",
+            "{}{}{}{}",
+            Self::prefix(),
             color::Fg(color::Yellow),
             msg,
             color::Fg(color::Reset)
@@ -22,7 +37,8 @@ impl PrintLine {
 
     pub fn error(msg: &str) {
         eprintln!(
-            "{}{}{}",
+            "{}{}{}{}",
+            Self::prefix(),
             color::Fg(color::Red),
             msg,
             color::Fg(color::Reset)
@@ -30,11 +46,25 @@ impl PrintLine {
     }
 
     pub fn success(msg: &str) {
-        println!(
+        Self::out(&format!(
             "{}{}{}",
             color::Fg(color::Green),
             msg,
             color::Fg(color::Reset)
-        )
+        ))
+    }
+
+    // In JSON output, stdout is kept only for results.
+    fn out(msg: &str) {
+        match format() {
+            Format::Text => println!("{}{}", Self::prefix(), msg),
+            Format::Json => eprintln!("{}{}", Self::prefix(), msg),
+        }
+    }
+
+    fn prefix() -> String {
+        PREFIX
+            .try_with(|prefix| prefix.to_owned())
+            .unwrap_or_default()
     }
 }
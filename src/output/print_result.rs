@@ -0,0 +1,21 @@
+use serde::Serialize;
+use serde_json;
+
+use super::format::{format, Format};
+
+/// Prints structured results of commands, one JSON object per line.
+/// Nothing is printed unless the output format is JSON.
+pub struct PrintResult {}
+
+impl PrintResult {
+    pub fn emit<T: Serialize>(result: &T) {
+        if format() != Format::Json {
+            return;
+        }
+
+        match serde_json::to_string(result) {
+            Ok(line) => println!("{}", line),
+            Err(err) => error!("Failed serializing the result: {}", err),
+        }
+    }
+}
@@ -0,0 +1,35 @@
+/// Formats seconds since the Unix epoch, as AWS APIs return, in UTC.
+pub fn format_timestamp(epoch_seconds: f64) -> String {
+    let seconds = epoch_seconds.floor() as i64;
+    let days = seconds.div_euclid(86400);
+    let time = seconds.rem_euclid(86400);
+
+    // Converts days since the epoch to a civil date.
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+#[test]
+fn test_format_timestamp() {
+    assert_eq!(format_timestamp(0.0), "1970-01-01 00:00:00 UTC");
+    assert_eq!(format_timestamp(951782400.5), "2000-02-29 00:00:00 UTC");
+    assert_eq!(format_timestamp(1622551845.0), "2021-06-01 12:50:45 UTC");
+}